@@ -0,0 +1,811 @@
+//! Command-line front end: argument parsing and dispatch to the output formats.
+
+use std::error::Error;
+use std::io::Write;
+
+use colored::*;
+
+use crate::output::{self, OutputFormat, RenderOptions};
+use crate::{Input, Options, Report};
+
+/// Options accepted on the command line, with the value placeholder and a description.
+const OPTION_HELP: &[(&str, &str)] = &[
+    (
+        "--format FORMAT",
+        "Output format: text (default), json, csv or tsv",
+    ),
+    (
+        "--summary-rows",
+        "Append per-pattern and grand total rows to csv/tsv output",
+    ),
+    (
+        "--top N",
+        "List the N most frequent words per file, per pattern and overall",
+    ),
+    (
+        "--tokenizer MODE",
+        "Word splitting: legacy (default) or unicode (UAX #29 word boundaries)",
+    ),
+    (
+        "--cjk",
+        "Count Han and kana per character, reported separately from words",
+    ),
+    (
+        "--stopwords-lang LIST",
+        "Leave out bundled stop words (en, de, fr, es) from unique and frequency statistics",
+    ),
+    (
+        "--stopwords FILE",
+        "Leave out the words listed in FILE (one per line, # comments); repeatable",
+    ),
+    (
+        "--files-from FILE",
+        "Count the files listed in FILE, one path per line ('-' for stdin); no glob expansion",
+    ),
+    (
+        "--files0-from FILE",
+        "Like --files-from, with NUL-separated paths (e.g. from find -print0)",
+    ),
+    (
+        "--stdin-format FORMAT",
+        "Format of the document read from '-' (txt, md, pdf, docx, odt, html, epub, pptx, xlsx, ods, rtf, tex, rst, adoc; default: guess)",
+    ),
+    (
+        "--exclude GLOB",
+        "Skip files and directories matching GLOB (path or file name); repeatable",
+    ),
+    (
+        "--no-ignore",
+        "Also count files excluded by .gitignore, .ignore and git exclude files",
+    ),
+    (
+        "--max-depth N",
+        "How deep to descend into directory arguments (1 = only files directly inside)",
+    ),
+    (
+        "--ext LIST",
+        "Extensions to count when walking directories (default: all supported)",
+    ),
+    (
+        "-j, --jobs N",
+        "Number of files to process in parallel (default: one per core)",
+    ),
+    (
+        "--md-include LIST",
+        "Count Markdown components normally skipped (code-blocks, link-urls, alt-text)",
+    ),
+    (
+        "--md-exclude LIST",
+        "Skip Markdown components normally counted (inline-code)",
+    ),
+    (
+        "--markup-include LIST",
+        "Count RST/AsciiDoc components normally skipped (literal-blocks)",
+    ),
+    (
+        "--markup-exclude LIST",
+        "Skip RST/AsciiDoc components normally counted (inline-literals)",
+    ),
+    (
+        "--html-include LIST",
+        "Count HTML components normally skipped (alt-text)",
+    ),
+    (
+        "--html-exclude LIST",
+        "Skip HTML components normally counted (nav)",
+    ),
+    (
+        "--html-select SELECTOR",
+        "Only count HTML text inside elements matching a CSS selector (e.g. main, article)",
+    ),
+    (
+        "--docx-include LIST",
+        "Add DOCX/ODT parts to the totals (headers, footers, footnotes, endnotes, comments)",
+    ),
+    (
+        "--docx-exclude LIST",
+        "Leave DOCX/ODT parts out of the totals (body); excluded parts are still reported",
+    ),
+    (
+        "--docx-changes MODE",
+        "Tracked changes: accept (default), reject, or summary (also report insertions/deletions)",
+    ),
+    (
+        "--pptx-include LIST",
+        "Add PPTX parts to the totals (notes)",
+    ),
+    (
+        "--pptx-exclude LIST",
+        "Leave PPTX parts out of the totals (slides); excluded parts are still reported",
+    ),
+    (
+        "--pptx-per-slide",
+        "List the word count of every slide and its speaker notes",
+    ),
+    (
+        "--latex-include LIST",
+        "Add LaTeX parts to the totals (body, headings, captions, footnotes)",
+    ),
+    (
+        "--latex-exclude LIST",
+        "Leave LaTeX parts out of the totals; excluded parts are still reported",
+    ),
+    (
+        "--sheets LIST",
+        "Only count these XLSX/ODS sheets (by name); the others are still reported",
+    ),
+    (
+        "--columns LIST",
+        "Only count these spreadsheet columns, by first-row header (Description) or else letter (C)",
+    ),
+    (
+        "--numeric-cells",
+        "Also count numeric, date and boolean spreadsheet cells",
+    ),
+];
+
+/// A parsed command line.
+#[derive(Debug, Default)]
+struct CliArgs {
+    options: Options,
+    format: OutputFormat,
+    render: RenderOptions,
+    inputs: Vec<Input>,
+}
+
+/// Splits the command line into options and inputs (file patterns and file lists).
+fn parse_args(args: &[String]) -> Result<CliArgs, Box<dyn Error>> {
+    let mut parsed = CliArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        // Accept both "--flag value" and "--flag=value".
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || -> Result<String, Box<dyn Error>> {
+            match inline_value.clone() {
+                Some(value) => Ok(value),
+                None => iter
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| format!("Missing value for {}", flag).into()),
+            }
+        };
+
+        match flag {
+            "--format" => parsed.format = value()?.parse()?,
+            "--summary-rows" => parsed.render.summary_rows = true,
+            "--top" => {
+                let top = value()?;
+                parsed.render.top = top
+                    .parse()
+                    .map_err(|_| format!("Invalid number of words '{}'", top))?;
+            }
+            "--tokenizer" => parsed.options.tokenizer = value()?.parse()?,
+            "--cjk" => parsed.options.cjk = true,
+            "--stopwords-lang" => {
+                for language in value()?.split(',') {
+                    parsed
+                        .options
+                        .stop_words
+                        .add_language(language.trim().parse()?);
+                }
+            }
+            "--stopwords" => parsed.options.stop_words.add_file(&value()?)?,
+            "--exclude" => parsed.options.walk.add_exclude(&value()?)?,
+            "--files-from" | "--files0-from" => parsed.inputs.push(Input::FileList {
+                source: value()?,
+                nul_separated: flag == "--files0-from",
+            }),
+            "--stdin-format" => parsed.options.stdin_format = Some(value()?.parse()?),
+            "--no-ignore" => parsed.options.walk.no_ignore = true,
+            "--max-depth" => {
+                let depth = value()?;
+                parsed.options.walk.max_depth = Some(
+                    depth
+                        .parse()
+                        .map_err(|_| format!("Invalid depth '{}'", depth))?,
+                );
+            }
+            "--ext" => {
+                for ext in value()?.split(',') {
+                    parsed.options.walk.extensions.push(ext.trim().to_string());
+                }
+            }
+            "--jobs" | "-j" => {
+                let jobs = value()?;
+                parsed.options.jobs = jobs
+                    .parse()
+                    .map_err(|_| format!("Invalid number of jobs '{}'", jobs))?;
+            }
+            "--md-include" | "--md-exclude" => {
+                let enabled = flag == "--md-include";
+                for name in value()?.split(',') {
+                    parsed.options.markdown.set(name.trim(), enabled)?;
+                }
+            }
+            "--markup-include" | "--markup-exclude" => {
+                let enabled = flag == "--markup-include";
+                for name in value()?.split(',') {
+                    parsed.options.markup.set(name.trim(), enabled)?;
+                }
+            }
+            "--html-include" | "--html-exclude" => {
+                let enabled = flag == "--html-include";
+                for name in value()?.split(',') {
+                    parsed.options.html.set(name.trim(), enabled)?;
+                }
+            }
+            "--html-select" => parsed.options.html.set_selector(&value()?)?,
+            "--docx-include" | "--docx-exclude" => {
+                let enabled = flag == "--docx-include";
+                for name in value()?.split(',') {
+                    parsed.options.docx.set(name.trim(), enabled)?;
+                }
+            }
+            "--docx-changes" => parsed.options.docx.changes = value()?.parse()?,
+            "--pptx-include" | "--pptx-exclude" => {
+                let enabled = flag == "--pptx-include";
+                for name in value()?.split(',') {
+                    parsed.options.pptx.set(name.trim(), enabled)?;
+                }
+            }
+            "--pptx-per-slide" => parsed.options.pptx.per_slide = true,
+            "--latex-include" | "--latex-exclude" => {
+                let enabled = flag == "--latex-include";
+                for name in value()?.split(',') {
+                    parsed.options.latex.set(name.trim(), enabled)?;
+                }
+            }
+            "--sheets" => {
+                for sheet in value()?.split(',') {
+                    parsed.options.sheets.sheets.push(sheet.trim().to_string());
+                }
+            }
+            "--columns" => {
+                for column in value()?.split(',') {
+                    parsed
+                        .options
+                        .sheets
+                        .columns
+                        .push(column.trim().to_string());
+                }
+            }
+            "--numeric-cells" => parsed.options.sheets.numbers = true,
+            _ if flag.starts_with("--") => return Err(format!("Unknown option '{}'", flag).into()),
+            _ => parsed.inputs.push(Input::Pattern(arg.clone())),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Prints the tool name and version.
+fn write_header(writer: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        writer,
+        "{} {}",
+        env!("CARGO_PKG_NAME").bright_cyan().bold(),
+        format!("v{}", env!("CARGO_PKG_VERSION")).bright_yellow()
+    )?;
+    Ok(())
+}
+
+fn write_usage(writer: &mut impl Write, binary: &str) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        writer,
+        "Usage: {} [options] <file_pattern|directory|-> [...]",
+        binary
+    )?;
+    writeln!(
+        writer,
+        "Supported file types: .txt, .md, .pdf, .docx, .odt, .html, .epub, .pptx, .xlsx, .ods, .rtf, .tex, .rst, .adoc"
+    )?;
+    writeln!(writer, "Options:")?;
+    for (option, description) in OPTION_HELP {
+        writeln!(writer, "  {:<23} {}", option, description)?;
+    }
+    writeln!(writer, "Examples:")?;
+    writeln!(writer, "  {} *.txt", binary)?;
+    writeln!(writer, "  {} *.pdf", binary)?;
+    writeln!(writer, "  {} *.docx", binary)?;
+    writeln!(writer, "  {} docs/*.{{txt,pdf,docx}}", binary)?;
+    writeln!(writer, "  {} --exclude drafts docs/", binary)?;
+    writeln!(writer, "  pandoc notes.docx -t plain | {} -", binary)?;
+    writeln!(writer, "  {} --format json \"docs/*.md\"", binary)?;
+    Ok(())
+}
+
+/// Runs the command line tool. `args[0]` is the binary name; output is written to `writer`.
+pub fn run(args: &[String], writer: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    if args.is_empty() {
+        // `args` must start with the binary name, as `std::env::args()` does.
+        return Err("Not enough arguments".into());
+    }
+
+    let cli = match parse_args(&args[1..]) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            writeln!(writer, "{}: {}", "Error".red().bold(), e)?;
+            return Err(e);
+        }
+    };
+
+    // Check if we have inputs (args[0] is the binary name)
+    if cli.inputs.is_empty() {
+        write_header(writer)?;
+        write_usage(writer, &args[0])?;
+        return Err("Invalid usage".into());
+    }
+
+    let report = Report::build_inputs(&cli.inputs, &cli.options)?;
+    if cli.format == OutputFormat::Text {
+        write_header(writer)?;
+    }
+    output::write_report(&report, cli.format, &cli.render, writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_docx_with_parts, create_test_file};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_markdown_options() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "notes.md", "Use `grep` here.");
+
+        let pattern = format!("{}/*.md", dir.path().to_str().unwrap());
+        let args = vec![
+            "mdwc".to_string(),
+            "--md-exclude=inline-code".to_string(),
+            pattern,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("2 unique words out of          2 total words"));
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--md-include".to_string(),
+            "nonsense".to_string(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .contains("Unknown Markdown component"));
+    }
+
+    #[test]
+    fn test_run_html_options() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(
+            &dir,
+            "index.html",
+            "<nav>Home</nav><main><p>Main text</p></main><script>skip()</script>",
+        );
+
+        let pattern = format!("{}/*.html", dir.path().to_str().unwrap());
+        let args = vec![
+            "mdwc".to_string(),
+            "--html-select=main".to_string(),
+            pattern.clone(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("2 unique words out of          2 total words"));
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--html-exclude=nav".to_string(),
+            pattern,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("2 unique words out of          2 total words"));
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--html-select".to_string(),
+            "p[".to_string(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .contains("Invalid CSS selector"));
+    }
+
+    #[test]
+    fn test_run_json_format() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.txt", "one two three");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let missing = format!("{}/*.pdf", dir.path().to_str().unwrap());
+        let args = vec![
+            "mdwc".to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+            pattern,
+            missing,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+
+        // The whole output must be a single JSON document, with no header or colors.
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(value["total"]["total_words"], 3);
+        assert_eq!(
+            value["patterns"][1]["error"],
+            "No files found matching the pattern"
+        );
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--format=yaml".to_string(),
+            "x".to_string(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_run_csv_format() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.txt", "one two three");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let args = vec![
+            "mdwc".to_string(),
+            "--format=csv".to_string(),
+            "--summary-rows".to_string(),
+            pattern,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("type,path,"));
+        assert_eq!(output.lines().count(), 4);
+        assert!(output.ends_with("total,,,,3,3,0,3,,\n"));
+    }
+
+    #[test]
+    fn test_run_jobs_option() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.txt", "alpha beta");
+        create_test_file(&dir, "b.txt", "gamma");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let mut outputs = Vec::new();
+        for jobs in ["1", "3"] {
+            let args = vec![
+                "mdwc".to_string(),
+                "-j".to_string(),
+                jobs.to_string(),
+                "--format=csv".to_string(),
+                pattern.clone(),
+            ];
+            let mut buffer = Vec::new();
+            assert!(run(&args, &mut buffer).is_ok());
+            outputs.push(String::from_utf8(buffer).unwrap());
+        }
+        assert_eq!(outputs[0], outputs[1]);
+
+        let args = vec!["mdwc".to_string(), "--jobs=many".to_string(), pattern];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .contains("Invalid number of jobs"));
+    }
+
+    #[test]
+    fn test_run_cjk_option() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "ja.txt", "日本語のテキスト and English");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let args = vec!["mdwc".to_string(), "--cjk".to_string(), pattern];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("2 total words + 8 CJK characters"));
+        assert!(output.contains("CJK characters:              8"));
+    }
+
+    #[test]
+    fn test_run_top_words() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.txt", "the cat and the hat and the bat");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let args = vec![
+            "mdwc".to_string(),
+            "--top".to_string(),
+            "2".to_string(),
+            pattern,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("Most frequent words:"));
+        // Listed for the file, the pattern and the grand total.
+        assert_eq!(output.matches("1. the").count(), 3);
+        assert_eq!(output.matches("2. and").count(), 3);
+        assert!(output.contains("37.50%"));
+        assert!(!output.contains("3. "));
+    }
+
+    #[test]
+    fn test_run_stop_words() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.txt", "the cat and the hat and the mdwc");
+        let list = create_test_file(&dir, "jargon.lst", "# tool names\nmdwc");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let args = vec![
+            "mdwc".to_string(),
+            "--stopwords-lang=en".to_string(),
+            "--stopwords".to_string(),
+            list,
+            "--format=json".to_string(),
+            pattern.clone(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(value["total"]["total_words"], 8);
+        assert_eq!(value["total"]["content_words"], 2);
+        assert_eq!(value["total"]["unique_words"], 2);
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--stopwords-lang=en".to_string(),
+            pattern,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("8 total words (3 without stop words)"));
+        assert!(output.contains("Without stop words:          3"));
+
+        let args = vec!["mdwc".to_string(), "--stopwords-lang=xx".to_string()];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .contains("No stop-word list for language 'xx'"));
+    }
+
+    #[test]
+    fn test_run_directory_walk() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("docs/drafts")).unwrap();
+        create_test_file(&dir, "docs/intro.md", "one two");
+        create_test_file(&dir, "docs/notes.txt", "three");
+        create_test_file(&dir, "docs/drafts/wip.md", "four five six");
+
+        let root = dir.path().join("docs").to_str().unwrap().to_string();
+        let csv_total = |extra: &[&str]| {
+            let mut args = vec!["mdwc".to_string(), "--format=csv".to_string()];
+            args.extend(extra.iter().map(|arg| arg.to_string()));
+            args.extend(["--summary-rows".to_string(), root.clone()]);
+            let mut buffer = Vec::new();
+            assert!(run(&args, &mut buffer).is_ok());
+            let output = String::from_utf8(buffer).unwrap();
+            output.lines().last().unwrap().to_string()
+        };
+
+        assert!(csv_total(&[]).starts_with("total,,,,6,"));
+        assert!(csv_total(&["--exclude", "drafts"]).starts_with("total,,,,3,"));
+        assert!(csv_total(&["--max-depth=1", "--ext", "md"]).starts_with("total,,,,2,"));
+
+        create_test_file(&dir, "docs/.gitignore", "notes.txt");
+        assert!(csv_total(&[]).starts_with("total,,,,5,"));
+        assert!(csv_total(&["--no-ignore"]).starts_with("total,,,,6,"));
+
+        let args = vec!["mdwc".to_string(), "--max-depth=deep".to_string(), root];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_parse_stdin_input() {
+        let args: Vec<String> = ["--stdin-format", "md", "-", "-j", "2"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let parsed = parse_args(&args).unwrap();
+        assert_eq!(parsed.inputs, vec![Input::Pattern("-".to_string())]);
+        assert_eq!(parsed.options.stdin_format, Some(crate::Format::Markdown));
+        assert_eq!(parsed.options.jobs, 2);
+
+        let args = vec!["--stdin-format=wpd".to_string(), "-".to_string()];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_run_files_from() {
+        let dir = TempDir::new().unwrap();
+        let first = create_test_file(&dir, "*literal*.txt", "one two");
+        let second = create_test_file(&dir, "b.txt", "three");
+        let list = create_test_file(&dir, "manifest.lst", &format!("{}\n{}", first, second));
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--format=csv".to_string(),
+            "--summary-rows".to_string(),
+            format!("--files-from={}", list),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(&format!(",--files-from {},txt,2,", list)));
+        assert!(output.ends_with("total,,,,3,3,0,3,,\n"));
+    }
+
+    #[test]
+    fn test_run_docx_parts() {
+        let dir = TempDir::new().unwrap();
+        create_docx_with_parts(
+            &dir,
+            "article.docx",
+            &[
+                ("word/document.xml", "Article body text"),
+                ("word/footnotes.xml", "Cited source"),
+            ],
+        );
+
+        let pattern = format!("{}/*.docx", dir.path().to_str().unwrap());
+        let args = vec!["mdwc".to_string(), pattern.clone()];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("3 total words"));
+        assert!(output.contains("footnotes:            2 words (not counted)"));
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--docx-include=footnotes".to_string(),
+            "--docx-exclude".to_string(),
+            "body".to_string(),
+            "--format=json".to_string(),
+            pattern.clone(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        let file = &value["patterns"][0]["files"][0];
+        assert_eq!(file["total_words"], 2);
+        assert_eq!(file["parts"][0]["name"], "body");
+        assert_eq!(file["parts"][0]["included"], false);
+        assert_eq!(file["parts"][1]["total_words"], 2);
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--docx-changes=summary".to_string(),
+            pattern.clone(),
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_ok());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("insertions:           0 words (not counted)"));
+
+        let args = vec![
+            "mdwc".to_string(),
+            "--docx-changes=merge".to_string(),
+            pattern,
+        ];
+        let mut buffer = Vec::new();
+        assert!(run(&args, &mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_run_usage() {
+        let args = vec!["mdwc".to_string()]; // No patterns provided
+        let mut buffer = Vec::new();
+
+        let result = run(&args, &mut buffer);
+        assert!(result.is_err()); // Should return "Invalid usage" or similar error
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("Usage:"));
+        assert!(output.contains("Supported file types:"));
+    }
+
+    #[test]
+    fn test_run_file_processing() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "run_test.txt", "hello run world");
+
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let args = vec!["mdwc".to_string(), pattern];
+        let mut buffer = Vec::new();
+
+        let result = run(&args, &mut buffer);
+        assert!(result.is_ok());
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("Analysis for files matching pattern"));
+        assert!(output.contains("run_test.txt"));
+        assert!(output.contains("3 unique words out of          3 total words"));
+        assert!(output.contains("GRAND TOTAL"));
+    }
+
+    #[test]
+    fn test_run_no_matching_files() {
+        let dir = TempDir::new().unwrap();
+        // Create no files
+        let pattern = format!("{}/*.txt", dir.path().to_str().unwrap());
+        let args = vec!["mdwc".to_string(), pattern];
+        let mut buffer = Vec::new();
+
+        let result = run(&args, &mut buffer);
+        assert!(result.is_ok()); // Should be ok, just prints error per pattern
+
+        let output = String::from_utf8(buffer).unwrap();
+        // Should contain the error for the pattern
+        assert!(output.contains("Error processing pattern"));
+        // Should NOT contain GRAND TOTAL
+        assert!(!output.contains("GRAND TOTAL"));
+    }
+
+    #[test]
+    fn test_process_invalid_pdf_integration() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "bad.pdf", "invalid pdf content");
+
+        let pattern = format!("{}/*.pdf", dir.path().to_str().unwrap());
+        let args = vec!["mdwc".to_string(), pattern];
+        let mut buffer = Vec::new();
+
+        // This will find the file, try to process it, fail at extraction,
+        // and print to stderr (which we don't capture here, but we execute the path).
+        // The run function itself should return Ok because it handled the error gracefully.
+        let result = run(&args, &mut buffer);
+        assert!(result.is_ok());
+
+        let output = String::from_utf8(buffer).unwrap();
+        // Since the error is printed to stderr in process_files (via eprintln!),
+        // and run() only prints to buffer on success of processing files,
+        // we might not see the file in the success list.
+        assert!(!output.contains("bad.pdf"));
+
+        // However, we verify that the Summary line is still printed (even if 0 files success)
+        // OR if the list was empty of successes, maybe it behaves differently.
+        // Actually, if results is empty (all failed), process_files returns Err("No files found...")
+        // Wait, process_files loop: if error occurs, it prints eprintln and continues.
+        // If ALL files fail, results is empty. process_files returns Err.
+        // So run() receives Err.
+
+        // Let's check process_files logic again.
+        // for entry in glob...
+        //    if path.is_file()
+        //       match count_words_in_file...
+        //          Ok -> results.push
+        //          Err -> eprintln (Line 84)
+        // if results.is_empty() -> Err("No files found...")
+
+        // So if we only have 1 bad file, results is empty, so run() gets Err.
+        // Let's include one GOOD file too, so process_files returns Ok, but still hits the error path for the bad one.
+        create_test_file(&dir, "good.txt", "hello");
+        let pattern_all = format!("{}/*.*", dir.path().to_str().unwrap());
+        let args_all = vec!["mdwc".to_string(), pattern_all];
+
+        let mut buffer2 = Vec::new(); // Use a new buffer
+        let result_all = run(&args_all, &mut buffer2);
+        // Now we should have 1 success, so process_files returns Ok.
+        assert!(result_all.is_ok());
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+use regex::{Captures, Regex};
+
+use crate::extract::include::{base_dir, read_include};
+use crate::extract::markup::MarkupOptions;
+
+/// Inline macros whose text is never shown as prose.
+const HIDDEN_MACROS: &[&str] = &[
+    "anchor",
+    "asciimath",
+    "image",
+    "indexterm",
+    "latexmath",
+    "pass",
+    "stem",
+];
+
+/// How the lines of a block are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Prose.
+    Text,
+    /// A listing or literal block, counted only with `literal_blocks`.
+    Literal,
+    /// Comments and passthrough content, never counted.
+    Skip,
+}
+
+/// Extracts the prose of an AsciiDoc document. Attribute entries, block attribute lines
+/// (`[source,rust]`), comments, conditional preprocessor directives, block macros such as
+/// `image::` and passthrough blocks are dropped, as are section and list markers, admonition
+/// labels (`NOTE:`) and table cell separators. Links and cross references count as their text,
+/// and attribute references are replaced by the values the document defines. `include::` is
+/// followed relative to the directory of `path`, or of the current directory when there is
+/// none.
+pub fn extract_asciidoc_text(
+    source: &str,
+    path: Option<&Path>,
+    options: &MarkupOptions,
+) -> Result<String, Box<dyn Error>> {
+    let mut reader = AsciiDocReader::new(options);
+    let mut text = String::new();
+    reader.process(source, &base_dir(path), 0, &mut text)?;
+    Ok(text)
+}
+
+struct AsciiDocReader<'a> {
+    options: &'a MarkupOptions,
+    /// Attributes defined so far, substituted for `{name}` references.
+    attributes: HashMap<String, String>,
+    /// Whether the reader is inside a `|===` table.
+    in_table: bool,
+    attribute_entry: Regex,
+    block_macro: Regex,
+    title: Regex,
+    admonition: Regex,
+    list_marker: Regex,
+    description_term: Regex,
+    table_cell: Regex,
+    quotes: Regex,
+    monospace: Regex,
+    inline_macro: Regex,
+    cross_reference: Regex,
+    anchor: Regex,
+    index_term: Regex,
+    attribute_reference: Regex,
+    opening_mark: Regex,
+    closing_mark: Regex,
+}
+
+impl<'a> AsciiDocReader<'a> {
+    fn new(options: &'a MarkupOptions) -> Self {
+        AsciiDocReader {
+            options,
+            attributes: HashMap::new(),
+            in_table: false,
+            attribute_entry: Regex::new(r"^:(!?)([\w][\w-]*)(!?):(?:\s+(.*))?$").unwrap(),
+            block_macro: Regex::new(r"^[a-z][\w-]*::\S*\[.*\]$").unwrap(),
+            title: Regex::new(r"^(?:=+|#+)\s+(.*?)(?:\s+=+)?$|^\.([^.\s].*)$").unwrap(),
+            admonition: Regex::new(r"^(?:NOTE|TIP|IMPORTANT|WARNING|CAUTION):\s+").unwrap(),
+            list_marker: Regex::new(r"^(?:\*+|-|\.+|\d+\.|[a-zA-Z]\.|<\d+>)\s+(?:\[[ xX*]\]\s+)?")
+                .unwrap(),
+            description_term: Regex::new(r"^(.*?\S)(?::{2,4}|;;)(\s+|$)").unwrap(),
+            table_cell: Regex::new(r"(^|\s)[\d.*+<>^]*[adehlmsv]?\|").unwrap(),
+            quotes: Regex::new(r#"(["'])`(.*?)`(["'])"#).unwrap(),
+            monospace: Regex::new(r"``(.+?)``|`([^`]+)`").unwrap(),
+            inline_macro: Regex::new(r"\b([a-z][\w-]*):(\S*?)\[([^\]]*)\]").unwrap(),
+            cross_reference: Regex::new(r"<<([^,>]+)(?:,\s*([^>]*))?>>").unwrap(),
+            anchor: Regex::new(r"\[\[[^\]]*\]\]|\[[.#%][^\]]*\]").unwrap(),
+            index_term: Regex::new(r"\(\(\((.*?)\)\)\)|\(\((.+?)\)\)").unwrap(),
+            attribute_reference: Regex::new(r"\{([\w][\w-]*)\}").unwrap(),
+            opening_mark: Regex::new(r#"(^|[\s(\[{'"])[*_#^~]+(\w)"#).unwrap(),
+            closing_mark: Regex::new(r#"(\w)[*_#^~]+([\s)\]}.,;:!?'"]|$)"#).unwrap(),
+        }
+    }
+
+    /// Appends the prose of `source` to `text`, resolving includes against `dir`.
+    fn process(
+        &mut self,
+        source: &str,
+        dir: &Path,
+        depth: usize,
+        text: &mut String,
+    ) -> Result<(), Box<dyn Error>> {
+        // The open delimited block that is not prose, with its closing delimiter.
+        let mut delimited: Option<(&str, Mode)> = None;
+        // The mode of the current paragraph; a literal or skipped one ends at a blank line.
+        let mut paragraph = Mode::Text;
+        let mut at_block_start = true;
+        // The style set by the last block attribute line, such as `source` or `comment`.
+        let mut style: Option<String> = None;
+
+        for line in source.lines() {
+            let trimmed = line.trim();
+
+            if let Some((closing, mode)) = delimited {
+                if line.trim_end() == closing {
+                    delimited = None;
+                    at_block_start = true;
+                } else if mode == Mode::Literal && self.options.literal_blocks {
+                    match trimmed.strip_prefix("include::") {
+                        Some(directive) => {
+                            let target = self.substitute(include_target(directive));
+                            text.push_str(&read_include(dir, &target, depth)?.0);
+                        }
+                        None => text.push_str(line),
+                    }
+                    text.push('\n');
+                }
+                continue;
+            }
+            if paragraph != Mode::Text {
+                if trimmed.is_empty() {
+                    paragraph = Mode::Text;
+                    at_block_start = true;
+                } else if paragraph == Mode::Literal && self.options.literal_blocks {
+                    text.push_str(trimmed);
+                    text.push('\n');
+                }
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                text.push('\n');
+                at_block_start = true;
+                continue;
+            }
+            if let Some(default) = delimiter_mode(line) {
+                let mode = match style.take().as_deref() {
+                    Some("source" | "listing" | "literal") => Mode::Literal,
+                    Some("comment" | "pass" | "stem" | "latexmath" | "asciimath") => Mode::Skip,
+                    _ => default,
+                };
+                if mode == Mode::Text {
+                    if line.trim_end().ends_with("===") && !line.starts_with('=') {
+                        self.in_table = !self.in_table;
+                    }
+                } else {
+                    delimited = Some((line.trim_end(), mode));
+                }
+                at_block_start = true;
+                continue;
+            }
+            if trimmed.starts_with("//") {
+                continue;
+            }
+            if let Some(caps) = self.attribute_entry.captures(trimmed) {
+                let name = caps[2].to_string();
+                if caps[1].is_empty() && caps[3].is_empty() {
+                    let value = caps.get(4).map_or("", |value| value.as_str());
+                    self.attributes.insert(name, value.to_string());
+                } else {
+                    self.attributes.remove(&name);
+                }
+                continue;
+            }
+            if trimmed.starts_with('[') && trimmed.ends_with(']') && at_block_start {
+                if !trimmed.starts_with("[[") {
+                    style = Some(block_style(trimmed));
+                }
+                continue;
+            }
+            if let Some(directive) = trimmed.strip_prefix("include::") {
+                let target = self.substitute(include_target(directive));
+                let (source, include_dir) = read_include(dir, &target, depth)?;
+                self.process(&source, &include_dir, depth + 1, text)?;
+                continue;
+            }
+            if ["ifdef::", "ifndef::", "ifeval::", "endif::"]
+                .iter()
+                .any(|directive| trimmed.starts_with(directive))
+                || self.block_macro.is_match(trimmed)
+                || ["+", "<<<", "'''", "---", "***"].contains(&trimmed)
+            {
+                continue;
+            }
+
+            if at_block_start {
+                let indented = line.starts_with(char::is_whitespace)
+                    && !self.list_marker.is_match(trimmed)
+                    && !self.in_table;
+                paragraph = match style.take().as_deref() {
+                    Some("source" | "listing" | "literal") => Mode::Literal,
+                    Some("comment" | "pass" | "stem" | "latexmath" | "asciimath") => Mode::Skip,
+                    _ if indented => Mode::Literal,
+                    _ => Mode::Text,
+                };
+                at_block_start = false;
+                if paragraph != Mode::Text {
+                    if paragraph == Mode::Literal && self.options.literal_blocks {
+                        text.push_str(trimmed);
+                        text.push('\n');
+                    }
+                    continue;
+                }
+            }
+            self.push_text(trimmed, text);
+        }
+        Ok(())
+    }
+
+    /// Appends a line of prose with its block markers and inline markup removed.
+    fn push_text(&self, line: &str, text: &mut String) {
+        let line = line.strip_suffix(" +").unwrap_or(line);
+        let line = match self.title.captures(line) {
+            Some(caps) => caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str()),
+            None => line,
+        };
+        let line = self.admonition.replace(line, "");
+        let line = self.list_marker.replace(&line, "");
+        let line = self.description_term.replace(&line, "$1$2");
+        let line = if self.in_table {
+            self.table_cell.replace_all(&line, "$1 ")
+        } else {
+            line
+        };
+
+        let line = self.quotes.replace_all(&line, "$1$2$3");
+        let line = self.monospace.replace_all(&line, |caps: &Captures| {
+            if self.options.inline_literals {
+                let content = caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str());
+                content.trim_matches('+').to_string()
+            } else {
+                " ".to_string()
+            }
+        });
+        let line = self.inline_macro.replace_all(&line, |caps: &Captures| {
+            let (name, target, content) = (&caps[1], &caps[2], &caps[3]);
+            if HIDDEN_MACROS.contains(&name) {
+                return String::new();
+            }
+            match name {
+                "menu" => format!("{} {}", target, content.replace('>', " ")),
+                "footnote" | "indexterm2" | "kbd" | "btn" => content.to_string(),
+                // Links and cross references show their text, falling back to the target
+                // only for macros that are not URLs.
+                _ => {
+                    let content = content.split(',').next().unwrap_or_default();
+                    let content = content.trim_matches('"').trim_end_matches('^');
+                    if !content.is_empty() || target.starts_with("//") {
+                        content.to_string()
+                    } else {
+                        target.to_string()
+                    }
+                }
+            }
+        });
+        let line = self.cross_reference.replace_all(&line, |caps: &Captures| {
+            caps.get(2).map_or("", |m| m.as_str()).to_string()
+        });
+        let line = self.anchor.replace_all(&line, "");
+        let line = self.index_term.replace_all(&line, "$2");
+        let line = self.substitute(&line);
+        let line = self.opening_mark.replace_all(&line, "$1$2");
+        let line = self.closing_mark.replace_all(&line, "$1$2");
+
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    /// Replaces attribute references with their values. Undefined attributes are dropped, and
+    /// `{nbsp}`, `{sp}` and `{empty}` count as whitespace.
+    fn substitute(&self, line: &str) -> String {
+        self.attribute_reference
+            .replace_all(line, |caps: &Captures| {
+                self.attributes
+                    .get(&caps[1])
+                    .cloned()
+                    .unwrap_or_else(|| " ".to_string())
+            })
+            .into_owned()
+    }
+}
+
+/// The mode of the block a delimiter line opens, or `None` if the line is not a delimiter.
+/// Example, sidebar, quote, open and table blocks hold prose.
+fn delimiter_mode(line: &str) -> Option<Mode> {
+    let line = line.trim_end();
+    if line == "--" || ["|===", ",===", ":===", "!==="].contains(&line) {
+        return Some(Mode::Text);
+    }
+    if line.starts_with("```") {
+        return Some(Mode::Literal);
+    }
+    let first = line.chars().next()?;
+    if line.len() < 4 || !line.chars().all(|c| c == first) {
+        return None;
+    }
+    match first {
+        '-' | '.' => Some(Mode::Literal),
+        '+' | '/' => Some(Mode::Skip),
+        '=' | '*' | '_' => Some(Mode::Text),
+        _ => None,
+    }
+}
+
+/// The style of a block attribute line: its first positional attribute without any id, role or
+/// option shorthand, e.g. `source` for `[source#main.rust,rust]`.
+fn block_style(line: &str) -> String {
+    let inner = &line[1..line.len() - 1];
+    let first = inner.split(',').next().unwrap_or_default();
+    let end = first.find(['#', '.', '%']).unwrap_or(first.len());
+    first[..end].trim().to_ascii_lowercase()
+}
+
+/// The path of an include directive (`include::path[attributes]`).
+fn include_target(directive: &str) -> &str {
+    directive
+        .rsplit_once('[')
+        .map_or(directive, |(target, _)| target)
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_file;
+    use tempfile::TempDir;
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn test_asciidoc_text() {
+        let source = "\
+= Document Title
+:author: Someone
+:product: Widget Pro
+
+== Overview
+
+// a comment
+NOTE: The {product} is *really* _fast_.
+
+[source,rust]
+----
+fn main() {}
+----
+
+.Block title
+* First `item`
+* See https://example.com[the site] and <<install,Installation>>.
+
+image::diagram.png[Diagram]
+
+CPU:: The brain
+
+////
+Comment block
+////
+
+|===
+| Name | Value
+a| Cell text | 42
+|===
+
+ indented literal line
+";
+        let text = extract_asciidoc_text(source, None, &MarkupOptions::default()).unwrap();
+        assert_eq!(
+            words(&text),
+            vec![
+                "Document",
+                "Title",
+                "Overview",
+                "The",
+                "Widget",
+                "Pro",
+                "is",
+                "really",
+                "fast.",
+                "Block",
+                "title",
+                "First",
+                "item",
+                "See",
+                "the",
+                "site",
+                "and",
+                "Installation.",
+                "CPU",
+                "The",
+                "brain",
+                "Name",
+                "Value",
+                "Cell",
+                "text",
+                "42"
+            ]
+        );
+
+        let options = MarkupOptions {
+            literal_blocks: true,
+            inline_literals: false,
+        };
+        let text = extract_asciidoc_text(source, None, &options).unwrap();
+        assert!(text.contains("fn main() {}") && text.contains("indented literal line"));
+        assert!(!text.contains("item"));
+    }
+
+    #[test]
+    fn test_asciidoc_include() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("chapters")).unwrap();
+        let main = create_test_file(
+            &dir,
+            "book.adoc",
+            ":chapters: chapters\n\nMain text\n\ninclude::{chapters}/one.adoc[leveloffset=+1]\n",
+        );
+        create_test_file(&dir, "chapters/one.adoc", "= One\n\ninclude::two.adoc[]\n");
+        create_test_file(&dir, "chapters/two.adoc", "Nested text\n");
+
+        let source = std::fs::read_to_string(&main).unwrap();
+        let options = MarkupOptions::default();
+        let text = extract_asciidoc_text(&source, Some(Path::new(&main)), &options).unwrap();
+        assert_eq!(words(&text), vec!["Main", "text", "One", "Nested", "text"]);
+        assert!(extract_asciidoc_text("include::missing.adoc[]", None, &options).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use std::error::Error;
+use std::fmt;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use zip::ZipArchive;
+
+use crate::extract::package::{attribute, numbered_entries, read_entry};
+use crate::extract::Part;
+
+/// How tracked changes (revisions) in a DOCX file are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrackedChanges {
+    /// Count the document as if every change were accepted: insertions kept, deletions dropped.
+    #[default]
+    Accept,
+    /// Count the original document, as if every change were rejected: insertions dropped,
+    /// deletions kept.
+    Reject,
+    /// Count as accepted, and also report the inserted and deleted text as `insertions` and
+    /// `deletions` parts that do not contribute to the totals.
+    Summary,
+}
+
+impl fmt::Display for TrackedChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrackedChanges::Accept => "accept",
+            TrackedChanges::Reject => "reject",
+            TrackedChanges::Summary => "summary",
+        })
+    }
+}
+
+impl FromStr for TrackedChanges {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "accept" | "accepted" => Ok(TrackedChanges::Accept),
+            "reject" | "rejected" | "original" => Ok(TrackedChanges::Reject),
+            "summary" => Ok(TrackedChanges::Summary),
+            _ => Err(format!("Unknown tracked-changes mode '{}'", s)),
+        }
+    }
+}
+
+/// Selects which parts of a DOCX file contribute to its totals. Every part present in the file
+/// is still counted and reported separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocxOptions {
+    /// The main document text.
+    pub body: bool,
+    pub headers: bool,
+    pub footers: bool,
+    pub footnotes: bool,
+    pub endnotes: bool,
+    /// Reviewer comments.
+    pub comments: bool,
+    pub changes: TrackedChanges,
+}
+
+impl Default for DocxOptions {
+    fn default() -> Self {
+        DocxOptions {
+            body: true,
+            headers: false,
+            footers: false,
+            footnotes: false,
+            endnotes: false,
+            comments: false,
+            changes: TrackedChanges::Accept,
+        }
+    }
+}
+
+impl DocxOptions {
+    /// Includes or excludes a part by its command-line name (`body`, `headers`, `footers`,
+    /// `footnotes`, `endnotes` or `comments`).
+    pub fn set(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        match name {
+            "body" => self.body = enabled,
+            "headers" => self.headers = enabled,
+            "footers" => self.footers = enabled,
+            "footnotes" => self.footnotes = enabled,
+            "endnotes" => self.endnotes = enabled,
+            "comments" => self.comments = enabled,
+            _ => return Err(format!("Unknown DOCX part '{}'", name)),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn includes(&self, name: &str) -> bool {
+        match name {
+            "body" => self.body,
+            "headers" => self.headers,
+            "footers" => self.footers,
+            "footnotes" => self.footnotes,
+            "endnotes" => self.endnotes,
+            "comments" => self.comments,
+            _ => false,
+        }
+    }
+}
+
+/// Extracts text from a DOCX file by opening it as a ZIP archive and walking the
+/// WordprocessingML of "word/document.xml", with all tracked changes accepted.
+pub fn extract_docx_text(bytes: &[u8]) -> Result<String, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let xml = read_entry(&mut archive, "word/document.xml")?;
+    Ok(wordprocessing_text(&xml, TrackedChanges::Accept)?.text)
+}
+
+/// Extracts every part of a DOCX file that is present: the body ("word/document.xml"), then
+/// headers, footers, footnotes, endnotes and comments. Numbered parts such as
+/// "word/header2.xml" are combined in numeric order. In [`TrackedChanges::Summary`] mode the
+/// text inserted and deleted in included parts follows as `insertions` and `deletions`.
+pub fn extract_docx_parts(
+    bytes: &[u8],
+    options: &DocxOptions,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut parts = Vec::new();
+    let mut inserted = String::new();
+    let mut deleted = String::new();
+
+    for (name, prefix) in [
+        ("body", "word/document"),
+        ("headers", "word/header"),
+        ("footers", "word/footer"),
+        ("footnotes", "word/footnotes"),
+        ("endnotes", "word/endnotes"),
+        ("comments", "word/comments"),
+    ] {
+        let entries = numbered_entries(&archive, prefix);
+        if entries.is_empty() {
+            if name == "body" {
+                return Err("word/document.xml not found in the DOCX archive".into());
+            }
+            continue;
+        }
+        let included = options.includes(name);
+        let mut text = String::new();
+        for entry in entries {
+            let revision =
+                wordprocessing_text(&read_entry(&mut archive, &entry)?, options.changes)?;
+            text.push_str(&revision.text);
+            text.push('\n');
+            if included {
+                inserted.push_str(&revision.inserted);
+                deleted.push_str(&revision.deleted);
+            }
+        }
+        parts.push(Part {
+            name: name.to_string(),
+            text,
+            included,
+        });
+    }
+
+    if options.changes == TrackedChanges::Summary {
+        for (name, text) in [("insertions", inserted), ("deletions", deleted)] {
+            parts.push(Part {
+                name: name.to_string(),
+                text,
+                included: false,
+            });
+        }
+    }
+
+    Ok(parts)
+}
+
+/// The text of a WordprocessingML part, with the text of tracked changes set aside.
+#[derive(Debug, Default)]
+struct Revision {
+    /// The text as counted in the selected [`TrackedChanges`] mode.
+    text: String,
+    /// Text inside insertions (`w:ins`, `w:moveTo`).
+    inserted: String,
+    /// Text inside deletions (`w:del`, `w:moveFrom`).
+    deleted: String,
+    changes: TrackedChanges,
+    insert_depth: usize,
+    delete_depth: usize,
+}
+
+impl Revision {
+    /// Appends displayed text, routing it according to the tracked change it belongs to.
+    fn push(&mut self, content: &str) {
+        if self.delete_depth > 0 {
+            self.deleted.push_str(content);
+            if self.changes == TrackedChanges::Reject {
+                self.text.push_str(content);
+            }
+        } else if self.insert_depth > 0 {
+            self.inserted.push_str(content);
+            if self.changes != TrackedChanges::Reject {
+                self.text.push_str(content);
+            }
+        } else {
+            self.text.push_str(content);
+        }
+    }
+}
+
+/// Converts a WordprocessingML part to the text Word displays.
+///
+/// Text runs (`w:t`, and `w:delText` inside deletions) are concatenated as-is, so a word Word
+/// split across several runs stays one word; paragraphs end with a line break, and `w:tab`,
+/// `w:br` and `w:cr` become whitespace. Entities are decoded. Tracked changes are resolved
+/// according to `changes`; field instructions (only the displayed field result is kept) and
+/// the fallback copies of `mc:AlternateContent` are skipped.
+fn wordprocessing_text(xml: &str, changes: TrackedChanges) -> Result<Revision, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut revision = Revision {
+        changes,
+        ..Revision::default()
+    };
+    let mut in_text = false;
+    let mut run_depth = 0usize;
+    let mut fallback_depth = 0usize;
+    // One entry per open complex field: false until its `separate` mark, while the field
+    // instruction is being read.
+    let mut fields: Vec<bool> = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"t" | b"delText" => in_text = true,
+                b"r" => run_depth += 1,
+                b"ins" | b"moveTo" => revision.insert_depth += 1,
+                b"del" | b"moveFrom" => revision.delete_depth += 1,
+                b"Fallback" => fallback_depth += 1,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" | b"delText" => in_text = false,
+                b"r" => run_depth = run_depth.saturating_sub(1),
+                b"ins" | b"moveTo" => {
+                    revision.inserted.push('\n');
+                    revision.insert_depth = revision.insert_depth.saturating_sub(1);
+                }
+                b"del" | b"moveFrom" => {
+                    revision.deleted.push('\n');
+                    revision.delete_depth = revision.delete_depth.saturating_sub(1);
+                }
+                b"Fallback" => fallback_depth = fallback_depth.saturating_sub(1),
+                b"p" => revision.text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) => {
+                if fallback_depth > 0 || run_depth == 0 {
+                    continue;
+                }
+                match e.local_name().as_ref() {
+                    b"tab" | b"ptab" => revision.push("\t"),
+                    b"br" | b"cr" => revision.push("\n"),
+                    b"noBreakHyphen" => revision.push("-"),
+                    b"fldChar" => match attribute(&e, b"fldCharType").as_deref() {
+                        Some("begin") => fields.push(false),
+                        Some("separate") => {
+                            if let Some(separated) = fields.last_mut() {
+                                *separated = true;
+                            }
+                        }
+                        Some("end") => {
+                            fields.pop();
+                        }
+                        _ => {}
+                    },
+                    _ => {}
+                }
+            }
+            Event::Text(e) if in_text && fallback_depth == 0 && fields.iter().all(|&done| done) => {
+                revision.push(&e.unescape()?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(revision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_docx_with_parts;
+    use crate::{tokenize, Tokenizer};
+
+    fn words(body: &str) -> Vec<String> {
+        revision_words(body, TrackedChanges::Accept).0
+    }
+
+    /// The counted, inserted and deleted words of a document body.
+    fn revision_words(
+        body: &str,
+        changes: TrackedChanges,
+    ) -> (Vec<String>, Vec<String>, Vec<String>) {
+        let xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" \
+             xmlns:mc=\"http://schemas.openxmlformats.org/markup-compatibility/2006\">\
+             <w:body>{}</w:body></w:document>",
+            body
+        );
+        let revision = wordprocessing_text(&xml, changes).unwrap();
+        (
+            tokenize(&revision.text, Tokenizer::Legacy),
+            tokenize(&revision.inserted, Tokenizer::Legacy),
+            tokenize(&revision.deleted, Tokenizer::Legacy),
+        )
+    }
+
+    #[test]
+    fn test_split_runs_stay_one_word() {
+        let body = "<w:p><w:r><w:t>Hel</w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>lo</w:t></w:r>\
+                    <w:r><w:t xml:space=\"preserve\"> wor</w:t></w:r><w:r><w:t>ld</w:t></w:r></w:p>";
+        assert_eq!(words(body), vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_paragraphs_tabs_and_breaks_separate_words() {
+        let body = "<w:p><w:pPr><w:tabs><w:tab w:val=\"left\" w:pos=\"720\"/></w:tabs></w:pPr>\
+                    <w:r><w:t>one</w:t><w:tab/><w:t>two</w:t><w:br/><w:t>three</w:t></w:r></w:p>\
+                    <w:p><w:r><w:t>four</w:t></w:r></w:p>";
+        assert_eq!(words(body), vec!["one", "two", "three", "four"]);
+    }
+
+    #[test]
+    fn test_entities_are_decoded() {
+        let body = "<w:p><w:r><w:t>Fish&amp;Chips &#x2014; caf&#233; &lt;tag&gt;</w:t></w:r></w:p>";
+        assert_eq!(words(body), vec!["fish", "chips", "café", "tag"]);
+    }
+
+    #[test]
+    fn test_deleted_text_is_skipped() {
+        let body = "<w:p><w:r><w:t xml:space=\"preserve\">kept </w:t></w:r>\
+                    <w:del w:id=\"1\" w:author=\"A\"><w:r><w:delText>removed</w:delText></w:r></w:del>\
+                    <w:ins w:id=\"2\" w:author=\"A\"><w:r><w:t>added</w:t></w:r></w:ins></w:p>";
+        assert_eq!(words(body), vec!["kept", "added"]);
+    }
+
+    #[test]
+    fn test_tracked_changes_modes() {
+        let body = "<w:p><w:r><w:t xml:space=\"preserve\">The </w:t></w:r>\
+                    <w:del w:id=\"1\" w:author=\"A\"><w:r><w:delText>old draft</w:delText></w:r></w:del>\
+                    <w:ins w:id=\"2\" w:author=\"A\"><w:r><w:t>new final text</w:t></w:r></w:ins>\
+                    <w:r><w:t xml:space=\"preserve\"> here</w:t></w:r></w:p>\
+                    <w:p><w:ins w:id=\"3\" w:author=\"B\"><w:r><w:t>Added</w:t><w:tab/><w:t>later</w:t></w:r></w:ins></w:p>";
+
+        let (accepted, inserted, deleted) = revision_words(body, TrackedChanges::Accept);
+        assert_eq!(
+            accepted,
+            vec!["the", "new", "final", "text", "here", "added", "later"]
+        );
+        assert_eq!(inserted, vec!["new", "final", "text", "added", "later"]);
+        assert_eq!(deleted, vec!["old", "draft"]);
+
+        let (rejected, _, _) = revision_words(body, TrackedChanges::Reject);
+        assert_eq!(rejected, vec!["the", "old", "draft", "here"]);
+
+        assert_eq!("original".parse(), Ok(TrackedChanges::Reject));
+        assert!("merge".parse::<TrackedChanges>().is_err());
+    }
+
+    #[test]
+    fn test_field_codes_keep_only_results() {
+        let body = "<w:p><w:r><w:t xml:space=\"preserve\">See page </w:t></w:r>\
+                    <w:r><w:fldChar w:fldCharType=\"begin\"/></w:r>\
+                    <w:r><w:instrText xml:space=\"preserve\"> PAGEREF _Toc1 \\h </w:instrText></w:r>\
+                    <w:r><w:fldChar w:fldCharType=\"separate\"/></w:r>\
+                    <w:r><w:t>seven</w:t></w:r>\
+                    <w:r><w:fldChar w:fldCharType=\"end\"/></w:r>\
+                    <w:r><w:t xml:space=\"preserve\"> by </w:t></w:r>\
+                    <w:fldSimple w:instr=\" AUTHOR \"><w:r><w:t>Ada</w:t></w:r></w:fldSimple></w:p>";
+        assert_eq!(words(body), vec!["see", "page", "seven", "by", "ada"]);
+    }
+
+    #[test]
+    fn test_parts_are_extracted_separately() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = create_docx_with_parts(
+            &dir,
+            "parts.docx",
+            &[
+                ("word/document.xml", "Main text here"),
+                ("word/footer2.xml", "second footer"),
+                ("word/footer1.xml", "first footer"),
+                ("word/footnotes.xml", "A footnote"),
+            ],
+        );
+        let bytes = std::fs::read(path).unwrap();
+
+        let options = DocxOptions {
+            footnotes: true,
+            ..DocxOptions::default()
+        };
+        let parts = extract_docx_parts(&bytes, &options).unwrap();
+        let summary: Vec<(&str, Vec<String>, bool)> = parts
+            .iter()
+            .map(|part| {
+                let words = tokenize(&part.text, Tokenizer::Legacy);
+                (part.name.as_str(), words, part.included)
+            })
+            .collect();
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0], ("body", words_of("main text here"), true));
+        assert_eq!(
+            summary[1],
+            ("footers", words_of("first footer second footer"), false)
+        );
+        assert_eq!(summary[2], ("footnotes", words_of("a footnote"), true));
+
+        let mut options = DocxOptions::default();
+        options.set("body", false).unwrap();
+        assert!(!extract_docx_parts(&bytes, &options).unwrap()[0].included);
+        assert!(options.set("sidebars", true).is_err());
+
+        let options = DocxOptions {
+            changes: TrackedChanges::Summary,
+            ..DocxOptions::default()
+        };
+        let parts = extract_docx_parts(&bytes, &options).unwrap();
+        let names: Vec<&str> = parts.iter().map(|part| part.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["body", "footers", "footnotes", "insertions", "deletions"]
+        );
+        assert!(!parts[3].included);
+    }
+
+    fn words_of(text: &str) -> Vec<String> {
+        tokenize(text, Tokenizer::Legacy)
+    }
+
+    #[test]
+    fn test_alternate_content_counted_once() {
+        let body = "<w:p><w:r><mc:AlternateContent>\
+                    <mc:Choice Requires=\"wps\"><w:txbxContent><w:p><w:r><w:t>boxed</w:t></w:r></w:p></w:txbxContent></mc:Choice>\
+                    <mc:Fallback><w:txbxContent><w:p><w:r><w:t>boxed</w:t></w:r></w:p></w:txbxContent></mc:Fallback>\
+                    </mc:AlternateContent></w:r></w:p>";
+        assert_eq!(words(body), vec!["boxed"]);
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Cursor;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use scraper::{Html, Selector};
+use zip::ZipArchive;
+
+use crate::extract::html::{extract_html_text, HtmlOptions};
+use crate::extract::package::{attribute, read_entry, resolve};
+use crate::extract::Part;
+
+/// A content document listed in the OPF manifest.
+#[derive(Debug, Default)]
+struct ManifestItem {
+    /// Archive path, resolved against the OPF file.
+    path: String,
+    media_type: String,
+    properties: String,
+}
+
+/// What the OPF package document says about the book.
+#[derive(Debug, Default)]
+struct Package {
+    manifest: HashMap<String, ManifestItem>,
+    /// Manifest ids in reading order.
+    spine: Vec<String>,
+    /// Manifest id of the EPUB 2 NCX table of contents.
+    toc: Option<String>,
+}
+
+/// Extracts an EPUB book as one part per chapter, in spine order. Each XHTML content document
+/// is read with the HTML extractor, so `options` apply to every chapter.
+///
+/// Chapters are named after their entry in the table of contents (the EPUB 3 navigation
+/// document, or the EPUB 2 NCX file). A content document without an entry of its own, such as
+/// the second half of a chapter split across files, is added to the chapter before it; one
+/// that comes before any titled chapter (a cover or title page) is named after its file. The
+/// navigation document itself is reported but not counted.
+pub fn extract_epub_parts(
+    bytes: &[u8],
+    options: &HtmlOptions,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let opf_path = rootfile_path(&read_entry(&mut archive, "META-INF/container.xml")?)?;
+    let package = parse_package(&read_entry(&mut archive, &opf_path)?, &opf_path)?;
+
+    let nav = package
+        .manifest
+        .values()
+        .find(|item| item.properties.split_whitespace().any(|p| p == "nav"));
+    let titles = match (
+        nav,
+        package.toc.as_ref().and_then(|id| package.manifest.get(id)),
+    ) {
+        (Some(nav), _) => nav_titles(&read_entry(&mut archive, &nav.path)?, &nav.path),
+        (None, Some(ncx)) => ncx_titles(&read_entry(&mut archive, &ncx.path)?, &ncx.path)?,
+        (None, None) => HashMap::new(),
+    };
+
+    let mut parts: Vec<Part> = Vec::new();
+    // Whether the last part is a titled chapter that untitled files can be added to.
+    let mut in_chapter = false;
+    for id in &package.spine {
+        let Some(item) = package.manifest.get(id) else {
+            continue;
+        };
+        if !matches!(
+            item.media_type.as_str(),
+            "application/xhtml+xml" | "text/html"
+        ) {
+            continue;
+        }
+        let text = extract_html_text(&read_entry(&mut archive, &item.path)?, options)?;
+        let is_nav = nav.is_some_and(|nav| nav.path == item.path);
+
+        match (titles.get(&item.path), parts.last_mut()) {
+            (None, Some(chapter)) if in_chapter && !is_nav => {
+                chapter.text.push('\n');
+                chapter.text.push_str(&text);
+            }
+            (title, _) => {
+                in_chapter = title.is_some() && !is_nav;
+                parts.push(Part {
+                    name: title
+                        .cloned()
+                        .unwrap_or_else(|| file_name(&item.path).to_string()),
+                    text,
+                    included: !is_nav,
+                });
+            }
+        }
+    }
+
+    Ok(parts)
+}
+
+/// Finds the OPF package document named in `META-INF/container.xml`.
+fn rootfile_path(container: &str) -> Result<String, Box<dyn Error>> {
+    let mut reader = Reader::from_str(container);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"rootfile" => {
+                if let Some(path) = attribute(&e, b"full-path") {
+                    return Ok(path);
+                }
+            }
+            Event::Eof => return Err("No rootfile found in META-INF/container.xml".into()),
+            _ => {}
+        }
+    }
+}
+
+/// Reads the manifest and spine of an OPF package document.
+fn parse_package(opf: &str, opf_path: &str) -> Result<Package, Box<dyn Error>> {
+    let mut reader = Reader::from_str(opf);
+    let mut package = Package::default();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"item" => {
+                    let (Some(id), Some(href)) = (attribute(&e, b"id"), attribute(&e, b"href"))
+                    else {
+                        continue;
+                    };
+                    package.manifest.insert(
+                        id,
+                        ManifestItem {
+                            path: resolve(opf_path, &href),
+                            media_type: attribute(&e, b"media-type").unwrap_or_default(),
+                            properties: attribute(&e, b"properties").unwrap_or_default(),
+                        },
+                    );
+                }
+                b"spine" => package.toc = attribute(&e, b"toc"),
+                b"itemref" => package.spine.extend(attribute(&e, b"idref")),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(package)
+}
+
+/// Maps each content document to the first title the EPUB 3 navigation document gives it,
+/// reading the `toc` navigation (or the first `<nav>` if none is marked).
+fn nav_titles(source: &str, nav_path: &str) -> HashMap<String, String> {
+    let document = Html::parse_document(source);
+    let navs = Selector::parse("nav").unwrap();
+    let links = Selector::parse("a[href]").unwrap();
+
+    let mut titles = HashMap::new();
+    let toc = document
+        .select(&navs)
+        .find(|nav| {
+            nav.value()
+                .attr("epub:type")
+                .is_some_and(|kind| kind.split_whitespace().any(|k| k == "toc"))
+        })
+        .or_else(|| document.select(&navs).next());
+    if let Some(toc) = toc {
+        for link in toc.select(&links) {
+            let href = link.value().attr("href").unwrap_or_default();
+            let title = collapse_whitespace(&link.text().collect::<String>());
+            if !title.is_empty() {
+                titles.entry(resolve(nav_path, href)).or_insert(title);
+            }
+        }
+    }
+    titles
+}
+
+/// Maps each content document to the first title the EPUB 2 NCX file gives it.
+fn ncx_titles(ncx: &str, ncx_path: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(ncx);
+    let mut titles = HashMap::new();
+    // The label of each open navPoint, innermost last.
+    let mut labels: Vec<String> = Vec::new();
+    let mut in_label = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"navPoint" => labels.push(String::new()),
+                b"text" => in_label = !labels.is_empty(),
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"navPoint" => {
+                    labels.pop();
+                }
+                b"text" => in_label = false,
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"content" => {
+                let (Some(src), Some(label)) = (attribute(&e, b"src"), labels.last()) else {
+                    continue;
+                };
+                let title = collapse_whitespace(label);
+                if !title.is_empty() {
+                    titles.entry(resolve(ncx_path, &src)).or_insert(title);
+                }
+            }
+            Event::Text(e) if in_label => {
+                if let Some(label) = labels.last_mut() {
+                    label.push_str(&e.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(titles)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_zip_file;
+    use tempfile::TempDir;
+
+    const CONTAINER: &str = "<?xml version=\"1.0\"?>\
+        <container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\
+        <rootfiles><rootfile full-path=\"OEBPS/content.opf\" \
+        media-type=\"application/oebps-package+xml\"/></rootfiles></container>";
+
+    fn page(body: &str) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <html xmlns=\"http://www.w3.org/1999/xhtml\"><head><title>Book</title></head>\
+            <body>{}</body></html>",
+            body
+        )
+    }
+
+    fn word_counts(parts: &[Part]) -> Vec<(&str, usize, bool)> {
+        parts
+            .iter()
+            .map(|part| {
+                (
+                    part.name.as_str(),
+                    part.text.split_whitespace().count(),
+                    part.included,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_epub3_chapters_in_spine_order() {
+        let opf = "<?xml version=\"1.0\"?>\
+            <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\"><manifest>\
+            <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\
+            <item id=\"cover\" href=\"text/cover.xhtml\" media-type=\"application/xhtml+xml\"/>\
+            <item id=\"c2\" href=\"text/chapter%202.xhtml\" media-type=\"application/xhtml+xml\"/>\
+            <item id=\"c1\" href=\"text/ch1.xhtml\" media-type=\"application/xhtml+xml\"/>\
+            <item id=\"c1b\" href=\"text/ch1_split.xhtml\" media-type=\"application/xhtml+xml\"/>\
+            <item id=\"css\" href=\"style.css\" media-type=\"text/css\"/>\
+            </manifest><spine>\
+            <itemref idref=\"cover\"/><itemref idref=\"nav\"/><itemref idref=\"c1\"/>\
+            <itemref idref=\"c1b\"/><itemref idref=\"c2\"/></spine></package>";
+        let nav = page(
+            "<nav epub:type=\"landmarks\"><ol><li><a href=\"text/cover.xhtml\">Cover</a></li></ol></nav>\
+            <nav epub:type=\"toc\"><ol>\
+            <li><a href=\"text/ch1.xhtml\">The\n Beginning</a>\
+            <ol><li><a href=\"text/ch1.xhtml#s1\">A section</a></li></ol></li>\
+            <li><a href=\"text/chapter%202.xhtml\">The End</a></li></ol></nav>",
+        );
+        let cover = page("<h1>My Book</h1>");
+        let ch1 = page("<h1>One</h1><p>First part.</p>");
+        let ch1_split = page("<p>Second part of one.</p>");
+        let ch2 = page("<h1>Two</h1><p>Last words here.</p>");
+
+        let dir = TempDir::new().unwrap();
+        let path = create_zip_file(
+            &dir,
+            "book.epub",
+            &[
+                ("mimetype", "application/epub+zip"),
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", opf),
+                ("OEBPS/nav.xhtml", &nav),
+                ("OEBPS/text/cover.xhtml", &cover),
+                ("OEBPS/text/ch1.xhtml", &ch1),
+                ("OEBPS/text/ch1_split.xhtml", &ch1_split),
+                ("OEBPS/text/chapter 2.xhtml", &ch2),
+            ],
+        );
+        let parts =
+            extract_epub_parts(&std::fs::read(path).unwrap(), &HtmlOptions::default()).unwrap();
+
+        assert_eq!(
+            word_counts(&parts),
+            vec![
+                ("cover.xhtml", 2, true),
+                ("nav.xhtml", 7, false),
+                ("The Beginning", 7, true),
+                ("The End", 4, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_epub2_ncx_titles() {
+        let opf = "<?xml version=\"1.0\"?>\
+            <opf:package xmlns:opf=\"http://www.idpf.org/2007/opf\" version=\"2.0\"><opf:manifest>\
+            <opf:item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\
+            <opf:item id=\"a\" href=\"a.html\" media-type=\"application/xhtml+xml\"/>\
+            </opf:manifest><opf:spine toc=\"ncx\"><opf:itemref idref=\"a\"/></opf:spine></opf:package>";
+        let ncx = "<?xml version=\"1.0\"?>\
+            <ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\"><docTitle><text>Book</text></docTitle>\
+            <navMap><navPoint id=\"p1\"><navLabel><text>Preface &amp; Thanks</text></navLabel>\
+            <content src=\"a.html#top\"/></navPoint></navMap></ncx>";
+        let a = page("<p>Thanks to everyone.</p>");
+
+        let dir = TempDir::new().unwrap();
+        let path = create_zip_file(
+            &dir,
+            "old.epub",
+            &[
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", opf),
+                ("OEBPS/toc.ncx", ncx),
+                ("OEBPS/a.html", &a),
+            ],
+        );
+        let parts =
+            extract_epub_parts(&std::fs::read(path).unwrap(), &HtmlOptions::default()).unwrap();
+        assert_eq!(word_counts(&parts), vec![("Preface & Thanks", 3, true)]);
+    }
+}
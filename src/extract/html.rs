@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::LazyLock;
+
+use ego_tree::iter::Edge;
+use ego_tree::NodeRef;
+use regex::Regex;
+use scraper::{Html, Node, Selector};
+
+/// Elements whose contents are never shown as text.
+const INVISIBLE: &[&str] = &[
+    "head", "script", "style", "template", "noscript", "iframe", "object", "svg", "math", "canvas",
+    "audio", "video", "select", "textarea",
+];
+
+/// Elements that flow with the surrounding text; every other element starts a new line so
+/// words in adjacent blocks (`<p>a</p><p>b</p>`) stay apart.
+const INLINE: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "del", "dfn", "em", "font", "i", "img",
+    "ins", "kbd", "label", "mark", "q", "s", "samp", "small", "span", "strong", "sub", "sup",
+    "time", "u", "var", "wbr",
+];
+
+/// Controls which parts of an HTML page contribute to the word count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Count the text of `<nav>` elements (menus, breadcrumbs, tables of contents).
+    pub nav: bool,
+    /// Count image alt text.
+    pub alt_text: bool,
+    /// Only count the text inside elements matching this CSS selector, such as `main` or
+    /// `article`. `None` counts the whole page.
+    pub selector: Option<String>,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        HtmlOptions {
+            nav: true,
+            alt_text: false,
+            selector: None,
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Enables or disables a component by its command-line name (`nav` or `alt-text`).
+    pub fn set(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        match name {
+            "nav" => self.nav = enabled,
+            "alt-text" => self.alt_text = enabled,
+            _ => return Err(format!("Unknown HTML component '{}'", name)),
+        }
+        Ok(())
+    }
+
+    /// Restricts counting to the elements matching a CSS selector, checking that it parses.
+    pub fn set_selector(&mut self, selector: &str) -> Result<(), String> {
+        Selector::parse(selector)
+            .map_err(|e| format!("Invalid CSS selector '{}': {}", selector, e))?;
+        self.selector = Some(selector.to_string());
+        Ok(())
+    }
+}
+
+/// Extracts the text a browser would show from an HTML or XHTML page. Scripts, styles, the
+/// `<head>` and elements with the `hidden` attribute are skipped, entities are decoded by the
+/// parser, and block elements are separated by line breaks. With a selector, only the matching
+/// elements are counted (an element inside another match is counted once); a page with no
+/// match has no text.
+pub fn extract_html_text(source: &str, options: &HtmlOptions) -> Result<String, Box<dyn Error>> {
+    let document = Html::parse_document(&close_void_raw_text(source));
+    let mut text = String::new();
+
+    match &options.selector {
+        Some(selector) => {
+            let selector = Selector::parse(selector)
+                .map_err(|e| format!("Invalid CSS selector '{}': {}", selector, e))?;
+            let matches: Vec<_> = document.select(&selector).collect();
+            let ids: HashSet<_> = matches.iter().map(|element| element.id()).collect();
+            for element in matches {
+                if !element.ancestors().any(|node| ids.contains(&node.id())) {
+                    push_visible_text(*element, options, &mut text);
+                }
+            }
+        }
+        None => push_visible_text(document.tree.root(), options, &mut text),
+    }
+
+    Ok(text)
+}
+
+/// Rewrites XHTML-style self-closing `<script/>`, `<style/>` and `<title/>` tags as empty
+/// elements. An HTML parser ignores the slash and would otherwise treat the rest of the page
+/// as the element's raw text.
+fn close_void_raw_text(source: &str) -> std::borrow::Cow<'_, str> {
+    static SELF_CLOSED: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)<(script|style|title|textarea|noscript|iframe)(\s[^<>]*?)?/>").unwrap()
+    });
+    SELF_CLOSED.replace_all(source, "<$1$2></$1>")
+}
+
+/// Appends the visible text under `root`, walking the tree iteratively so deeply nested pages
+/// cannot overflow the stack.
+fn push_visible_text(root: NodeRef<'_, Node>, options: &HtmlOptions, text: &mut String) {
+    let mut skipping = None;
+
+    for edge in root.traverse() {
+        match edge {
+            Edge::Open(node) => {
+                if skipping.is_some() {
+                    continue;
+                }
+                match node.value() {
+                    Node::Text(content) => text.push_str(content),
+                    Node::Element(element) => {
+                        let name = element.name();
+                        if INVISIBLE.contains(&name)
+                            || (name == "nav" && !options.nav)
+                            || element.attr("hidden").is_some()
+                        {
+                            skipping = Some(node.id());
+                            continue;
+                        }
+                        if name == "img" && options.alt_text {
+                            if let Some(alt) = element.attr("alt") {
+                                text.push(' ');
+                                text.push_str(alt);
+                                text.push(' ');
+                            }
+                        }
+                        if !INLINE.contains(&name) {
+                            text.push('\n');
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Edge::Close(node) => {
+                if skipping == Some(node.id()) {
+                    skipping = None;
+                } else if skipping.is_none() {
+                    if let Node::Element(element) = node.value() {
+                        if !INLINE.contains(&element.name()) {
+                            text.push('\n');
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tokenize, Tokenizer};
+
+    const PAGE: &str = "<!DOCTYPE html>
+<html><head><title>Site title</title><style>p { color: red; }</style></head>
+<body>
+<nav><a href=\"/\">Home</a> <a href=\"/docs\">Docs</a></nav>
+<main><h1>Getting&nbsp;started</h1><p>Fish &amp; chips</p><p>in<b>line</b></p>
+<img src=\"a.png\" alt=\"diagram\"><p hidden>secret</p>
+<script>var ignored = 1;</script></main>
+<footer>Copyright</footer>
+</body></html>";
+
+    fn words(source: &str, options: &HtmlOptions) -> Vec<String> {
+        tokenize(
+            &extract_html_text(source, options).unwrap(),
+            Tokenizer::Legacy,
+        )
+    }
+
+    #[test]
+    fn test_visible_text_only() {
+        assert_eq!(
+            words(PAGE, &HtmlOptions::default()),
+            vec![
+                "home",
+                "docs",
+                "getting",
+                "started",
+                "fish",
+                "chips",
+                "inline",
+                "copyright"
+            ]
+        );
+
+        let mut options = HtmlOptions::default();
+        options.set("nav", false).unwrap();
+        options.set("alt-text", true).unwrap();
+        assert_eq!(
+            words(PAGE, &options),
+            vec![
+                "getting",
+                "started",
+                "fish",
+                "chips",
+                "inline",
+                "diagram",
+                "copyright"
+            ]
+        );
+        assert!(options.set("header", false).is_err());
+    }
+
+    #[test]
+    fn test_selector() {
+        let mut options = HtmlOptions::default();
+        options.set_selector("main").unwrap();
+        assert_eq!(
+            words(PAGE, &options),
+            vec!["getting", "started", "fish", "chips", "inline"]
+        );
+
+        // Nested matches are counted once; a page without a match has no words.
+        options.set_selector("main, p").unwrap();
+        assert_eq!(words(PAGE, &options).len(), 5);
+        options.set_selector("article").unwrap();
+        assert!(words(PAGE, &options).is_empty());
+        assert!(options.set_selector("main[").is_err());
+    }
+
+    #[test]
+    fn test_xhtml_self_closing_script() {
+        let source = "<?xml version=\"1.0\"?><html xmlns=\"http://www.w3.org/1999/xhtml\">\
+            <head><title/><script src=\"a.js\"/></head><body><p>Still counted</p></body></html>";
+        assert_eq!(
+            words(source, &HtmlOptions::default()),
+            vec!["still", "counted"]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Reading the files that LaTeX, reStructuredText and AsciiDoc sources include.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How deeply includes may nest, as a guard against include cycles.
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+/// The directory a document's includes are resolved against: the document's own directory, or
+/// the current directory when it has no path.
+pub(crate) fn base_dir(path: Option<&Path>) -> PathBuf {
+    path.and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// Reads the file an include directive names, relative to `dir`, returning its contents and
+/// the directory its own includes are resolved against. `depth` is the nesting level of the
+/// document containing the directive.
+pub(crate) fn read_include(
+    dir: &Path,
+    target: &str,
+    depth: usize,
+) -> Result<(String, PathBuf), Box<dyn Error>> {
+    if depth >= MAX_INCLUDE_DEPTH {
+        return Err(format!("Includes nested too deeply at '{}'", target).into());
+    }
+    let path = dir.join(target);
+    let source = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "Cannot read included file '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok((source, base_dir(Some(&path))))
+}
@@ -0,0 +1,650 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::extract::include::{base_dir, read_include};
+use crate::extract::Part;
+
+/// The parts a LaTeX document is split into, in report order.
+const PARTS: [&str; 4] = ["body", "headings", "captions", "footnotes"];
+const BODY: usize = 0;
+const HEADINGS: usize = 1;
+const CAPTIONS: usize = 2;
+const FOOTNOTES: usize = 3;
+
+/// How deeply command arguments may nest, as a guard against running out of stack on
+/// pathological input.
+const MAX_NESTING_DEPTH: usize = 256;
+
+/// Environments whose contents are dropped: mathematics, code listings and drawings.
+const SKIPPED_ENVIRONMENTS: &[&str] = &[
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "comment",
+    "displaymath",
+    "eqnarray",
+    "eqnarray*",
+    "equation",
+    "equation*",
+    "flalign",
+    "flalign*",
+    "gather",
+    "gather*",
+    "lstlisting",
+    "math",
+    "minted",
+    "multline",
+    "multline*",
+    "tikzpicture",
+    "verbatim",
+    "verbatim*",
+];
+
+/// Selects which parts of a LaTeX document count towards its totals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatexOptions {
+    /// Running text.
+    pub body: bool,
+    /// Sectioning titles, from `\part` down to `\subparagraph`.
+    pub headings: bool,
+    /// Figure and table captions.
+    pub captions: bool,
+    /// Footnotes and margin notes.
+    pub footnotes: bool,
+}
+
+impl Default for LatexOptions {
+    fn default() -> Self {
+        LatexOptions {
+            body: true,
+            headings: true,
+            captions: true,
+            footnotes: true,
+        }
+    }
+}
+
+impl LatexOptions {
+    /// Includes or excludes a part by its command-line name (`body`, `headings`, `captions` or
+    /// `footnotes`).
+    pub fn set(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        match name {
+            "body" => self.body = enabled,
+            "headings" => self.headings = enabled,
+            "captions" => self.captions = enabled,
+            "footnotes" => self.footnotes = enabled,
+            _ => return Err(format!("Unknown LaTeX part '{}'", name)),
+        }
+        Ok(())
+    }
+
+    fn includes(&self, part: usize) -> bool {
+        [self.body, self.headings, self.captions, self.footnotes][part]
+    }
+}
+
+/// What a command does with its arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    /// Its `n` arguments are text, counted where the command appears (`\emph`, `\textbf`).
+    Keep(usize),
+    /// Its `n` arguments are not text (`\label`, `\cite`, `\includegraphics`).
+    Drop(usize),
+    /// Drops the first argument and keeps the second (`\href{url}{text}`).
+    DropKeep,
+    /// Its argument goes to another part.
+    Route(usize),
+    /// Reads another source file in its place.
+    Include,
+    /// A line break.
+    Break,
+}
+
+fn command(name: &str) -> Option<Command> {
+    let name = name.trim_end_matches('*');
+    Some(match name {
+        "part" | "chapter" | "section" | "subsection" | "subsubsection" | "paragraph"
+        | "subparagraph" => Command::Route(HEADINGS),
+        "caption" | "captionof" => Command::Route(CAPTIONS),
+        "footnote" | "footnotetext" | "marginpar" => Command::Route(FOOTNOTES),
+        "input" | "include" | "subfile" => Command::Include,
+        "emph" | "textbf" | "textit" | "texttt" | "textsc" | "textsf" | "textrm" | "textup"
+        | "textsl" | "textmd" | "textnormal" | "underline" | "uline" | "mbox" | "hbox" | "text"
+        | "enquote" | "textquote" => Command::Keep(1),
+        "href" | "textcolor" | "colorbox" => Command::DropKeep,
+        "label" | "ref" | "eqref" | "pageref" | "autoref" | "cref" | "Cref" | "nameref"
+        | "cite" | "citep" | "citet" | "citeauthor" | "citeyear" | "nocite" | "parencite"
+        | "textcite" | "url" | "includegraphics" | "usepackage" | "RequirePackage"
+        | "documentclass" | "bibliography" | "bibliographystyle" | "addbibresource" | "vspace"
+        | "hspace" | "pagestyle" | "thispagestyle" | "pagenumbering" | "index" | "color"
+        | "graphicspath" | "hypersetup" | "includeonly" | "fontsize" | "linespread"
+        | "setcounter" | "addtocounter" | "setlength" | "addtolength" | "renewenvironment"
+        | "newcounter" => Command::Drop(drop_count(name)),
+        "newcommand" | "renewcommand" | "providecommand" | "DeclareMathOperator" => {
+            Command::Drop(2)
+        }
+        "newenvironment" => Command::Drop(3),
+        "par" | "newline" | "linebreak" | "newpage" | "clearpage" => Command::Break,
+        _ => return None,
+    })
+}
+
+/// The number of arguments of the dropped commands that take more than one.
+fn drop_count(name: &str) -> usize {
+    match name {
+        "setcounter" | "addtocounter" | "setlength" | "addtolength" | "fontsize" => 2,
+        "renewenvironment" => 3,
+        _ => 1,
+    }
+}
+
+/// The number of arguments that follow `\begin{name}` and are not text, such as a table's
+/// column specification.
+fn environment_arguments(name: &str) -> usize {
+    match name {
+        "tabular" | "array" | "minipage" | "multicols" | "thebibliography" | "longtable" => 1,
+        "tabular*" | "tabularx" | "wrapfigure" | "wraptable" => 2,
+        _ => 0,
+    }
+}
+
+/// Extracts a LaTeX document as `body`, `headings`, `captions` and `footnotes` parts. When
+/// the source (or an included file) has a `\begin{document}`, only the text between it and
+/// `\end{document}` is read.
+/// `\input`, `\include` and `\subfile` are followed relative to the directory of `path` (or the
+/// current directory when there is none), adding `.tex` when the name has no extension.
+///
+/// Inline and display mathematics, maths environments, verbatim text and listings are dropped,
+/// as are comments and the arguments of commands such as `\label`, `\cite` and `\ref`. Text
+/// commands like `\emph` keep their arguments, and unknown commands are dropped while any
+/// braced text after them is counted.
+pub fn extract_latex_parts(
+    source: &str,
+    path: Option<&Path>,
+    options: &LatexOptions,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut extractor = Extractor {
+        base: base_dir(path),
+        texts: Default::default(),
+        depth: 0,
+        nesting: 0,
+    };
+    extractor.process(document_body(source), BODY)?;
+
+    Ok(PARTS
+        .iter()
+        .zip(extractor.texts)
+        .enumerate()
+        .filter(|(index, (_, text))| *index == BODY || !text.trim().is_empty())
+        .map(|(index, (name, text))| Part {
+            name: name.to_string(),
+            text,
+            included: options.includes(index),
+        })
+        .collect())
+}
+
+struct Extractor {
+    /// The directory included files are resolved against.
+    base: PathBuf,
+    texts: [String; 4],
+    /// How many includes deep the current source is.
+    depth: usize,
+    /// How many command arguments deep the current fragment is.
+    nesting: usize,
+}
+
+impl Extractor {
+    /// Appends the text of a LaTeX fragment to the `target` part (or to the part a command
+    /// inside it routes to).
+    fn process(&mut self, source: &str, target: usize) -> Result<(), Box<dyn Error>> {
+        if self.nesting >= MAX_NESTING_DEPTH {
+            return Err("Command arguments nested too deeply".into());
+        }
+        self.nesting += 1;
+        let result = self.process_fragment(source, target);
+        self.nesting -= 1;
+        result
+    }
+
+    fn process_fragment(&mut self, source: &str, target: usize) -> Result<(), Box<dyn Error>> {
+        let chars: Vec<char> = source.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '%' => i = skip_line(&chars, i),
+                '$' => {
+                    let delimiter = if chars.get(i + 1) == Some(&'$') {
+                        "$$"
+                    } else {
+                        "$"
+                    };
+                    i = skip_past(&chars, i + delimiter.len(), delimiter);
+                    self.texts[target].push(' ');
+                }
+                '{' | '}' => i += 1,
+                '~' | '&' => {
+                    self.texts[target].push(' ');
+                    i += 1;
+                }
+                '\\' => i = self.process_command(&chars, i, target)?,
+                c => {
+                    self.texts[target].push(c);
+                    i += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the control sequence starting at `start`, returning the position after it and
+    /// any arguments it consumed.
+    fn process_command(
+        &mut self,
+        chars: &[char],
+        start: usize,
+        target: usize,
+    ) -> Result<usize, Box<dyn Error>> {
+        let (name, mut i) = read_command_name(chars, start + 1);
+
+        if name.is_empty() {
+            // A control symbol.
+            let Some(&symbol) = chars.get(start + 1) else {
+                return Ok(start + 1);
+            };
+            match symbol {
+                '(' => return Ok(skip_past(chars, start + 2, "\\)")),
+                '[' => return Ok(skip_past(chars, start + 2, "\\]")),
+                '\\' => {
+                    self.texts[target].push('\n');
+                    // `\\[2pt]` and `\\*` take an optional spacing argument.
+                    let mut next = start + 2;
+                    if chars.get(next) == Some(&'*') {
+                        next += 1;
+                    }
+                    return Ok(skip_optional(chars, next));
+                }
+                '%' | '&' | '$' | '#' | '_' | '{' | '}' => self.texts[target].push(symbol),
+                ',' | ';' | ':' | ' ' | '\n' | '!' => self.texts[target].push(' '),
+                // Accents (`\'e`, `\"{o}`) keep only the letter, and `\-` is a hyphenation hint.
+                _ => {}
+            }
+            return Ok(start + 2);
+        }
+
+        match name.as_str() {
+            "begin" => {
+                let (environment, next) = read_argument(chars, i);
+                let environment = environment.unwrap_or_default();
+                if SKIPPED_ENVIRONMENTS.contains(&environment.trim()) {
+                    let end = format!("\\end{{{}}}", environment.trim());
+                    return Ok(skip_past(chars, next, &end));
+                }
+                i = skip_optional(chars, next);
+                for _ in 0..environment_arguments(environment.trim()) {
+                    i = skip_optional(chars, read_argument(chars, i).1);
+                }
+                self.texts[target].push('\n');
+                return Ok(i);
+            }
+            "end" => {
+                self.texts[target].push('\n');
+                return Ok(read_argument(chars, i).1);
+            }
+            // `\item[label]` in description lists; the label is not counted.
+            "item" => {
+                self.texts[target].push('\n');
+                return Ok(skip_optional(chars, i));
+            }
+            "bibitem" => {
+                self.texts[target].push('\n');
+                return Ok(read_argument(chars, skip_optional(chars, i)).1);
+            }
+            "verb" => {
+                let mut next = i;
+                if chars.get(next) == Some(&'*') {
+                    next += 1;
+                }
+                let Some(&delimiter) = chars.get(next) else {
+                    return Ok(next);
+                };
+                let end = chars[next + 1..]
+                    .iter()
+                    .position(|&c| c == delimiter)
+                    .map_or(chars.len(), |offset| next + 1 + offset + 1);
+                return Ok(end);
+            }
+            "def" | "gdef" | "edef" | "xdef" => {
+                // `\def\name#1{body}`: skip the name and parameter text, then the body.
+                let (_, next) = read_command_name(chars, i + 1);
+                let body = chars[next..]
+                    .iter()
+                    .position(|&c| c == '{')
+                    .map_or(chars.len(), |offset| next + offset);
+                return Ok(read_argument(chars, body).1);
+            }
+            _ => {}
+        }
+
+        match command(&name) {
+            Some(Command::Keep(count)) => {
+                for _ in 0..count {
+                    let (argument, next) = read_argument(chars, skip_optional(chars, i));
+                    self.process(&argument.unwrap_or_default(), target)?;
+                    i = next;
+                }
+            }
+            Some(Command::Drop(count)) => {
+                for _ in 0..count {
+                    i = skip_optional(chars, read_argument(chars, skip_optional(chars, i)).1);
+                }
+            }
+            Some(Command::DropKeep) => {
+                let (_, next) = read_argument(chars, skip_optional(chars, i));
+                let (argument, next) = read_argument(chars, next);
+                self.process(&argument.unwrap_or_default(), target)?;
+                i = next;
+            }
+            Some(Command::Route(part)) => {
+                let (argument, next) = read_argument(chars, skip_optional(chars, i));
+                self.process(&argument.unwrap_or_default(), part)?;
+                self.texts[part].push('\n');
+                i = next;
+            }
+            Some(Command::Include) => {
+                let (argument, next) = read_file_name(chars, i);
+                if let Some(name) = argument {
+                    self.include(name.trim(), target)?;
+                }
+                i = next;
+            }
+            Some(Command::Break) => self.texts[target].push('\n'),
+            None => self.texts[target].push(' '),
+        }
+        Ok(i)
+    }
+
+    /// Reads an included source file and processes it in place.
+    fn include(&mut self, name: &str, target: usize) -> Result<(), Box<dyn Error>> {
+        let path = self.base.join(name);
+        let name = if path.extension().is_none() || !path.exists() {
+            format!("{}.tex", name)
+        } else {
+            name.to_string()
+        };
+        // Unlike in RST and AsciiDoc, nested includes stay relative to the main file.
+        let (source, _) = read_include(&self.base, &name, self.depth)?;
+
+        self.depth += 1;
+        let result = self.process(document_body(&source), target);
+        self.depth -= 1;
+        result
+    }
+}
+
+/// The text between `\begin{document}` and `\end{document}`, or the whole source when it has
+/// no document environment (as in most included files, though not in `\subfile`s).
+fn document_body(source: &str) -> &str {
+    match source.split_once("\\begin{document}") {
+        Some((_, rest)) => rest.split("\\end{document}").next().unwrap_or(rest),
+        None => source,
+    }
+}
+
+/// Reads the letters of a command name (and a trailing `*`), returning it and the position
+/// after it. Spaces after a command name are consumed.
+fn read_command_name(chars: &[char], start: usize) -> (String, usize) {
+    let mut i = start;
+    while i < chars.len() && chars[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    if i == start {
+        return (String::new(), start);
+    }
+    if chars.get(i) == Some(&'*') {
+        i += 1;
+    }
+    let name: String = chars[start..i].iter().collect();
+    while i < chars.len() && (chars[i] == ' ' || chars[i] == '\t') {
+        i += 1;
+    }
+    (name, i)
+}
+
+/// Reads a mandatory argument: a braced group, or else a single command or character. Returns
+/// `None` at the end of the input.
+fn read_argument(chars: &[char], start: usize) -> (Option<String>, usize) {
+    let mut i = start;
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    match chars.get(i) {
+        None => (None, i),
+        Some('{') => {
+            let end = matching(chars, i, '{', '}');
+            let inner: String = chars[i + 1..end.min(chars.len())].iter().collect();
+            (Some(inner), (end + 1).min(chars.len()))
+        }
+        Some('\\') => {
+            let (name, next) = read_command_name(chars, i + 1);
+            if name.is_empty() {
+                let end = (i + 2).min(chars.len());
+                (Some(chars[i..end].iter().collect()), end)
+            } else {
+                (Some(format!("\\{}", name)), next)
+            }
+        }
+        Some(&c) => (Some(c.to_string()), i + 1),
+    }
+}
+
+/// Reads the file name of an include: a braced group, or else the characters up to the next
+/// whitespace or control sequence, as in `\input chapter1`.
+fn read_file_name(chars: &[char], start: usize) -> (Option<String>, usize) {
+    let mut i = start;
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    if chars.get(i) == Some(&'{') {
+        return read_argument(chars, i);
+    }
+    let end = chars[i..]
+        .iter()
+        .position(|&c| c.is_whitespace() || "\\{}%".contains(c))
+        .map_or(chars.len(), |offset| i + offset);
+    let name: String = chars[i..end].iter().collect();
+    (Some(name).filter(|name| !name.is_empty()), end)
+}
+
+/// Skips any optional `[...]` arguments (and the whitespace before them) at `start`.
+fn skip_optional(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    loop {
+        let mut next = i;
+        while next < chars.len() && chars[next].is_whitespace() {
+            next += 1;
+        }
+        if chars.get(next) != Some(&'[') {
+            return i;
+        }
+        i = (matching(chars, next, '[', ']') + 1).min(chars.len());
+    }
+}
+
+/// The position of the bracket closing the one at `open`, or the end of the input. Braced
+/// groups are skipped, and escaped brackets do not count.
+fn matching(chars: &[char], open: usize, left: char, right: char) -> usize {
+    let mut depth = 0usize;
+    let mut braces = 0usize;
+    let mut i = open;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '%' => {
+                i = skip_line(chars, i);
+                continue;
+            }
+            '{' if left != '{' => braces += 1,
+            '}' if left != '{' => braces = braces.saturating_sub(1),
+            c if c == left && braces == 0 => depth += 1,
+            c if c == right && braces == 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// The position after the end of the line containing `start`.
+fn skip_line(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .position(|&c| c == '\n')
+        .map_or(chars.len(), |offset| start + offset + 1)
+}
+
+/// The position after the next unescaped occurrence of `end` at or after `start`, or the end
+/// of the input.
+fn skip_past(chars: &[char], start: usize, end: &str) -> usize {
+    let end: Vec<char> = end.chars().collect();
+    let mut i = start;
+    while i + end.len() <= chars.len() {
+        if chars[i..i + end.len()] == end[..] {
+            return i + end.len();
+        }
+        if chars[i] == '\\' && end[0] != '\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_file;
+    use tempfile::TempDir;
+
+    fn words(parts: &[Part], name: &str) -> Vec<String> {
+        parts
+            .iter()
+            .find(|part| part.name == name)
+            .map(|part| part.text.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_latex_parts() {
+        let source = r"\documentclass{article}
+\usepackage[utf8]{inputenc}
+\title{Not counted}
+\begin{document}
+\section[Short]{Introduction}\label{sec:intro}
+Some \emph{important} text~here, see \cite{knuth} and \ref{fig:a}. % a comment
+Inline $E = mc^2$ math and \(x\) more.\footnote{A note with $y$.}
+\begin{equation}
+  a^2 + b^2 = c^2
+\end{equation}
+\begin{figure}[h]
+  \includegraphics[width=\linewidth]{plot.png}
+  \caption{Results \textbf{overview}}
+\end{figure}
+\begin{tabular}{ll}
+  left & right \\
+\end{tabular}
+Visit \href{https://example.com}{our site} for 50\% off.
+\verb|\code| done.
+\begin{description}
+  \item[Label] Described
+\end{description}
+\end{document}
+ignored after end";
+        let parts = extract_latex_parts(source, None, &LatexOptions::default()).unwrap();
+
+        assert_eq!(
+            words(&parts, "body"),
+            vec![
+                "Some",
+                "important",
+                "text",
+                "here,",
+                "see",
+                "and",
+                ".",
+                "Inline",
+                "math",
+                "and",
+                "more.",
+                "left",
+                "right",
+                "Visit",
+                "our",
+                "site",
+                "for",
+                "50%",
+                "off.",
+                "done.",
+                "Described"
+            ]
+        );
+        assert_eq!(words(&parts, "headings"), vec!["Introduction"]);
+        assert_eq!(words(&parts, "captions"), vec!["Results", "overview"]);
+        assert_eq!(words(&parts, "footnotes"), vec!["A", "note", "with", "."]);
+        assert!(parts.iter().all(|part| part.included));
+
+        let nested = format!("{}x{}", r"\emph{".repeat(1_000), "}".repeat(1_000));
+        let result = extract_latex_parts(&nested, None, &LatexOptions::default());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("nested too deeply"));
+    }
+
+    #[test]
+    fn test_latex_includes() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("chapters")).unwrap();
+        let main = create_test_file(
+            &dir,
+            "main.tex",
+            r"\begin{document}\input{chapters/one}\include{chapters/two.tex}\input chapters/three
+\end{document}",
+        );
+        create_test_file(&dir, "chapters/one.tex", r"\chapter{One} First chapter.");
+        create_test_file(&dir, "chapters/two.tex", r"Second \footnote{aside}");
+        create_test_file(&dir, "chapters/three.tex", "Third");
+
+        let mut options = LatexOptions::default();
+        options.set("footnotes", false).unwrap();
+        let source = std::fs::read_to_string(&main).unwrap();
+        let parts = extract_latex_parts(&source, Some(Path::new(&main)), &options).unwrap();
+        assert_eq!(
+            words(&parts, "body"),
+            vec!["First", "chapter.", "Second", "Third"]
+        );
+        assert_eq!(words(&parts, "headings"), vec!["One"]);
+        assert!(
+            !parts
+                .iter()
+                .find(|part| part.name == "footnotes")
+                .unwrap()
+                .included
+        );
+
+        create_test_file(&dir, "loop.tex", r"\input{loop}");
+        let result = extract_latex_parts(r"\input{loop}", Some(Path::new(&main)), &options);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("nested too deeply"));
+        assert!(extract_latex_parts(r"\input{missing}", None, &options).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::LazyLock;
+
+use pulldown_cmark::{Event, LinkType, Options as ParserOptions, Parser, Tag, TagEnd};
+use regex::Regex;
+
+/// Controls which non-prose parts of a Markdown document contribute to the word count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// Count the contents of fenced and indented code blocks.
+    pub code_blocks: bool,
+    /// Count `inline code` spans.
+    pub inline_code: bool,
+    /// Count link and image destinations (URLs and paths), including autolinks such as
+    /// `<https://example.com>`.
+    pub link_urls: bool,
+    /// Count image alt text.
+    pub alt_text: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            code_blocks: false,
+            inline_code: true,
+            link_urls: false,
+            alt_text: false,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// Enables or disables a component by its command-line name
+    /// (`code-blocks`, `inline-code`, `link-urls` or `alt-text`).
+    pub fn set(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        match name {
+            "code-blocks" => self.code_blocks = enabled,
+            "inline-code" => self.inline_code = enabled,
+            "link-urls" => self.link_urls = enabled,
+            "alt-text" => self.alt_text = enabled,
+            _ => return Err(format!("Unknown Markdown component '{}'", name)),
+        }
+        Ok(())
+    }
+}
+
+/// Renders a CommonMark document (with GFM tables, footnotes and task lists) to the prose a
+/// reader would see, dropping markup such as heading markers, table pipes, HTML comments,
+/// front matter and footnote labels.
+pub fn extract_markdown_text(source: &str, options: &MarkdownOptions) -> String {
+    let parser_options = ParserOptions::ENABLE_TABLES
+        | ParserOptions::ENABLE_FOOTNOTES
+        | ParserOptions::ENABLE_TASKLISTS
+        | ParserOptions::ENABLE_STRIKETHROUGH
+        | ParserOptions::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+
+    let mut text = String::new();
+    let mut html_block = String::new();
+    let mut in_code_block = false;
+    let mut in_html_block = false;
+    let mut in_metadata = false;
+    let mut image_depth = 0usize;
+    // The text of `<https://...>` and `<user@host>` links is the destination itself.
+    let mut in_autolink = false;
+
+    for event in Parser::new_ext(source, parser_options) {
+        match event {
+            Event::Start(tag) => {
+                match &tag {
+                    Tag::CodeBlock(_) => in_code_block = true,
+                    Tag::HtmlBlock => in_html_block = true,
+                    Tag::MetadataBlock(_) => in_metadata = true,
+                    Tag::Image { dest_url, .. } => {
+                        image_depth += 1;
+                        if options.link_urls {
+                            push_separated(&mut text, dest_url);
+                        }
+                    }
+                    Tag::Link {
+                        link_type: LinkType::Autolink | LinkType::Email,
+                        ..
+                    } => in_autolink = true,
+                    Tag::Link { dest_url, .. } if options.link_urls => {
+                        push_separated(&mut text, dest_url);
+                    }
+                    _ => {}
+                }
+                if !is_inline(&TagEnd::from(tag)) {
+                    text.push('\n');
+                }
+            }
+            Event::End(tag) => {
+                match tag {
+                    TagEnd::CodeBlock => in_code_block = false,
+                    TagEnd::HtmlBlock => {
+                        in_html_block = false;
+                        text.push_str(&strip_html(&html_block));
+                        html_block.clear();
+                    }
+                    TagEnd::MetadataBlock(_) => in_metadata = false,
+                    TagEnd::Image => image_depth = image_depth.saturating_sub(1),
+                    TagEnd::Link => in_autolink = false,
+                    TagEnd::TableCell => text.push(' '),
+                    _ => {}
+                }
+                if !is_inline(&tag) {
+                    text.push('\n');
+                }
+            }
+            Event::Text(content) => {
+                if in_metadata
+                    || (in_code_block && !options.code_blocks)
+                    || (image_depth > 0 && !options.alt_text)
+                    || (in_autolink && !options.link_urls)
+                {
+                    continue;
+                }
+                if image_depth > 0 {
+                    push_separated(&mut text, &content);
+                } else {
+                    text.push_str(&content);
+                }
+            }
+            Event::Code(content) if options.inline_code => text.push_str(&content),
+            Event::Html(content) if in_html_block => html_block.push_str(&content),
+            Event::SoftBreak | Event::HardBreak | Event::Rule => text.push('\n'),
+            // Inline HTML tags, footnote labels and task list markers are markup, not prose.
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// Returns true for tags that sit inside a line of text and therefore must not introduce a
+/// word break (e.g. `foo*bar*` renders as one word).
+fn is_inline(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+            | TagEnd::TableCell
+    )
+}
+
+/// Appends `content` surrounded by spaces so it cannot merge with adjacent words.
+fn push_separated(text: &mut String, content: &str) {
+    text.push(' ');
+    text.push_str(content);
+    text.push(' ');
+}
+
+/// Removes comments and tags from a raw HTML block, keeping the text between them.
+fn strip_html(html: &str) -> String {
+    static COMMENTS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+    static TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+    let without_comments = COMMENTS.replace_all(html, " ");
+    TAGS.replace_all(&without_comments, " ").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphabetic())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_lowercase())
+            .collect()
+    }
+
+    #[test]
+    fn test_markup_is_not_counted() {
+        let source =
+            "# Title\n\nSome *emphasised* text with a [link](http://example.com/path).\n\n\
+                      <!-- hidden comment -->\n\n![diagram](images/arch.png)\n";
+        let text = extract_markdown_text(source, &MarkdownOptions::default());
+        assert_eq!(
+            words(&text),
+            vec!["title", "some", "emphasised", "text", "with", "a", "link"]
+        );
+    }
+
+    #[test]
+    fn test_gfm_tables_footnotes_and_task_lists() {
+        let source = "| Name | Role |\n|------|------|\n| Ada | Author |\n\n\
+                      - [x] done task\n- [ ] open task\n\n\
+                      Claim[^note].\n\n[^note]: Source here.\n";
+        let text = extract_markdown_text(source, &MarkdownOptions::default());
+        assert_eq!(
+            words(&text),
+            vec![
+                "name", "role", "ada", "author", "done", "task", "open", "task", "claim", "source",
+                "here"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_options() {
+        let source = "Run `cargo build` now.\n\n```rust\nfn main() {}\n```\n";
+        let default_text = extract_markdown_text(source, &MarkdownOptions::default());
+        assert_eq!(words(&default_text), vec!["run", "cargo", "build", "now"]);
+
+        let options = MarkdownOptions {
+            code_blocks: true,
+            inline_code: false,
+            ..MarkdownOptions::default()
+        };
+        let text = extract_markdown_text(source, &options);
+        assert_eq!(words(&text), vec!["run", "now", "fn", "main"]);
+    }
+
+    #[test]
+    fn test_link_urls_and_alt_text_options() {
+        let source = "See [docs](guide) and ![logo image](logo.png).\n";
+        let mut options = MarkdownOptions::default();
+        options.set("link-urls", true).unwrap();
+        options.set("alt-text", true).unwrap();
+        let text = extract_markdown_text(source, &options);
+        assert_eq!(
+            words(&text),
+            vec!["see", "guide", "docs", "and", "logo", "png", "logo", "image"]
+        );
+        assert!(options.set("bogus", true).is_err());
+
+        let source = "See <https://example.com/some/long/path> or <me@example.com> now.\n";
+        let text = extract_markdown_text(source, &MarkdownOptions::default());
+        assert_eq!(words(&text), vec!["see", "or", "now"]);
+        let text = extract_markdown_text(source, &options);
+        assert!(words(&text).contains(&"example".to_string()));
+    }
+
+    #[test]
+    fn test_front_matter_and_inline_words() {
+        let source = "---\ntitle: Hidden\n---\n\nfoo**bar**baz\n";
+        let text = extract_markdown_text(source, &MarkdownOptions::default());
+        assert_eq!(words(&text), vec!["foobarbaz"]);
+    }
+}
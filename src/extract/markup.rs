@@ -0,0 +1,33 @@
+//! Options shared by the reStructuredText and AsciiDoc extractors.
+
+/// Controls which non-prose parts of a reStructuredText or AsciiDoc document contribute to the
+/// word count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkupOptions {
+    /// Count literal and listing blocks (`::` blocks, `code-block` directives, `----` blocks).
+    pub literal_blocks: bool,
+    /// Count inline literals (` ``code`` ` in RST, `` `code` `` in AsciiDoc).
+    pub inline_literals: bool,
+}
+
+impl Default for MarkupOptions {
+    fn default() -> Self {
+        MarkupOptions {
+            literal_blocks: false,
+            inline_literals: true,
+        }
+    }
+}
+
+impl MarkupOptions {
+    /// Enables or disables a component by its command-line name (`literal-blocks` or
+    /// `inline-literals`).
+    pub fn set(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        match name {
+            "literal-blocks" => self.literal_blocks = enabled,
+            "inline-literals" => self.inline_literals = enabled,
+            _ => return Err(format!("Unknown markup component '{}'", name)),
+        }
+        Ok(())
+    }
+}
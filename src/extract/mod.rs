@@ -0,0 +1,394 @@
+//! Format detection and text extraction.
+//!
+//! Every extractor works on an in-memory byte buffer so the same code path serves files,
+//! readers and strings.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::str::FromStr;
+
+use pdf_extract::extract_text_from_mem;
+use zip::ZipArchive;
+
+use crate::Options;
+
+mod asciidoc;
+mod docx;
+mod epub;
+mod html;
+mod include;
+mod latex;
+pub mod markdown;
+mod markup;
+mod odt;
+mod package;
+mod pptx;
+mod rst;
+mod rtf;
+mod spreadsheet;
+
+pub use asciidoc::extract_asciidoc_text;
+pub use docx::{extract_docx_parts, extract_docx_text, DocxOptions, TrackedChanges};
+pub use epub::extract_epub_parts;
+pub use html::{extract_html_text, HtmlOptions};
+pub use latex::{extract_latex_parts, LatexOptions};
+pub use markdown::{extract_markdown_text, MarkdownOptions};
+pub use markup::MarkupOptions;
+pub use odt::{extract_odt_parts, extract_odt_text};
+pub use pptx::{extract_pptx_parts, PptxOptions};
+pub use rst::extract_rst_text;
+pub use rtf::extract_rtf_text;
+pub use spreadsheet::{extract_ods_parts, extract_xlsx_parts, SheetOptions};
+
+/// File extensions with a dedicated extractor, picked up when walking a directory.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "pdf", "docx", "odt", "html", "htm", "xhtml", "epub", "pptx", "xlsx",
+    "ods", "rtf", "tex", "latex", "rst", "rest", "adoc", "asciidoc",
+];
+
+/// The name of the first entry of ODT and EPUB archives: an uncompressed file holding the
+/// media type, which starts 30 bytes into the file (after the local file header).
+const MIMETYPE_ENTRY: &[u8] = b"mimetype";
+
+/// A separately counted portion of a document, such as the footnotes of a DOCX file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    pub name: String,
+    pub text: String,
+    /// Whether the part contributes to the document's totals.
+    pub included: bool,
+}
+
+/// The text extracted from a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extracted {
+    /// The text counted towards the document's totals.
+    pub text: String,
+    /// A per-part breakdown, for formats made of separately countable parts; empty otherwise.
+    pub parts: Vec<Part>,
+}
+
+impl Extracted {
+    /// Builds the counted text from the included parts.
+    fn from_parts(parts: Vec<Part>) -> Extracted {
+        let text = parts
+            .iter()
+            .filter(|part| part.included)
+            .map(|part| part.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Extracted { text, parts }
+    }
+}
+
+impl From<String> for Extracted {
+    fn from(text: String) -> Self {
+        Extracted {
+            text,
+            parts: Vec::new(),
+        }
+    }
+}
+
+/// A document format understood by the extractors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Plain UTF-8 text, counted as-is.
+    Text,
+    /// CommonMark/GFM Markdown, rendered to prose before counting.
+    Markdown,
+    /// PDF documents.
+    Pdf,
+    /// Microsoft Word (Office Open XML) documents.
+    Docx,
+    /// OpenDocument text documents, as written by LibreOffice Writer.
+    Odt,
+    /// HTML and XHTML pages, counted as the text a browser would show.
+    Html,
+    /// EPUB e-books, counted chapter by chapter.
+    Epub,
+    /// PowerPoint (Office Open XML) presentations.
+    Pptx,
+    /// Excel (Office Open XML) workbooks, counted sheet by sheet.
+    Xlsx,
+    /// OpenDocument spreadsheets, counted sheet by sheet.
+    Ods,
+    /// Rich Text Format documents.
+    Rtf,
+    /// LaTeX sources, counted with their `\input` and `\include` files.
+    Latex,
+    /// reStructuredText documents, as used by Sphinx and docutils.
+    Rst,
+    /// AsciiDoc documents.
+    AsciiDoc,
+}
+
+impl Format {
+    /// Picks a format from a file extension (ignoring case), falling back to plain text.
+    pub fn from_path(path: &Path) -> Format {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("pdf") => Format::Pdf,
+            Some("docx") => Format::Docx,
+            Some("odt") => Format::Odt,
+            Some("epub") => Format::Epub,
+            Some("pptx") => Format::Pptx,
+            Some("xlsx") => Format::Xlsx,
+            Some("ods") => Format::Ods,
+            Some("rtf") => Format::Rtf,
+            Some("tex") | Some("latex") => Format::Latex,
+            Some("rst") | Some("rest") => Format::Rst,
+            Some("adoc") | Some("asciidoc") => Format::AsciiDoc,
+            Some("html") | Some("htm") | Some("xhtml") => Format::Html,
+            Some("md") | Some("markdown") => Format::Markdown,
+            _ => Format::Text,
+        }
+    }
+
+    /// Guesses the format of a document without a file name, such as one piped to stdin: PDFs
+    /// and ZIP-based files are recognised by their signature, anything else is plain text. ODT,
+    /// ODS and EPUB files are told apart from DOCX by their leading `mimetype` entry, PPTX and
+    /// XLSX files by their `ppt/presentation.xml` and `xl/workbook.xml` entries, RTF by its
+    /// `{\rtf` header, and HTML by a leading doctype or `<html>` tag.
+    pub fn sniff(bytes: &[u8]) -> Format {
+        let start = bytes
+            .strip_prefix(b"\xEF\xBB\xBF")
+            .unwrap_or(bytes)
+            .trim_ascii_start();
+        let is_html = |prefix: &[u8]| {
+            start
+                .get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+        };
+        if bytes.starts_with(b"%PDF-") {
+            Format::Pdf
+        } else if bytes.starts_with(b"{\\rtf") {
+            Format::Rtf
+        } else if bytes.starts_with(b"PK\x03\x04") {
+            let media_type = bytes
+                .get(30..)
+                .and_then(|rest| rest.strip_prefix(MIMETYPE_ENTRY))
+                .unwrap_or_default();
+            if media_type.starts_with(b"application/vnd.oasis.opendocument.text") {
+                Format::Odt
+            } else if media_type.starts_with(b"application/vnd.oasis.opendocument.spreadsheet") {
+                Format::Ods
+            } else if media_type.starts_with(b"application/epub+zip") {
+                Format::Epub
+            } else if has_entry(bytes, "ppt/presentation.xml") {
+                Format::Pptx
+            } else if has_entry(bytes, "xl/workbook.xml") {
+                Format::Xlsx
+            } else {
+                Format::Docx
+            }
+        } else if is_html(b"<!doctype html") || is_html(b"<html") {
+            Format::Html
+        } else {
+            Format::Text
+        }
+    }
+
+    /// The short name used on the command line and in reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Text => "txt",
+            Format::Markdown => "md",
+            Format::Pdf => "pdf",
+            Format::Docx => "docx",
+            Format::Odt => "odt",
+            Format::Html => "html",
+            Format::Epub => "epub",
+            Format::Pptx => "pptx",
+            Format::Xlsx => "xlsx",
+            Format::Ods => "ods",
+            Format::Rtf => "rtf",
+            Format::Latex => "tex",
+            Format::Rst => "rst",
+            Format::AsciiDoc => "adoc",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "txt" | "text" => Ok(Format::Text),
+            "md" | "markdown" => Ok(Format::Markdown),
+            "pdf" => Ok(Format::Pdf),
+            "docx" => Ok(Format::Docx),
+            "odt" => Ok(Format::Odt),
+            "epub" => Ok(Format::Epub),
+            "pptx" => Ok(Format::Pptx),
+            "xlsx" => Ok(Format::Xlsx),
+            "ods" => Ok(Format::Ods),
+            "rtf" => Ok(Format::Rtf),
+            "tex" | "latex" => Ok(Format::Latex),
+            "rst" | "rest" | "restructuredtext" => Ok(Format::Rst),
+            "adoc" | "asciidoc" => Ok(Format::AsciiDoc),
+            "html" | "htm" | "xhtml" => Ok(Format::Html),
+            _ => Err(format!("Unknown format '{}'", s)),
+        }
+    }
+}
+
+/// Whether a ZIP archive contains an entry with the given name.
+fn has_entry(bytes: &[u8], name: &str) -> bool {
+    ZipArchive::new(Cursor::new(bytes)).is_ok_and(|mut archive| archive.by_name(name).is_ok())
+}
+
+/// Extracts the countable text of a file, choosing the extractor from its extension.
+pub fn extract_file_content(file_path: &str, options: &Options) -> Result<String, Box<dyn Error>> {
+    let bytes = fs::read(file_path)?;
+    let path = Path::new(file_path);
+    Ok(extract_document_at(&bytes, Format::from_path(path), Some(path), options)?.text)
+}
+
+/// Extracts the countable text of an in-memory document. See [`extract_document`] for the
+/// per-part breakdown some formats provide.
+pub fn extract_bytes(
+    bytes: &[u8],
+    format: Format,
+    options: &Options,
+) -> Result<String, Box<dyn Error>> {
+    Ok(extract_document(bytes, format, options)?.text)
+}
+
+/// Extracts an in-memory document. Included files are looked up in the current directory; use
+/// [`extract_document_at`] to look them up next to the document.
+pub fn extract_document(
+    bytes: &[u8],
+    format: Format,
+    options: &Options,
+) -> Result<Extracted, Box<dyn Error>> {
+    extract_document_at(bytes, format, None, options)
+}
+
+/// Extracts an in-memory document read from `path`, looking up included files next to it. For
+/// PDFs it uses `pdf_extract`, Office and OpenDocument files are unzipped and their XML parts
+/// walked element by element, EPUB chapters are read in spine order, RTF control words are
+/// interpreted, Markdown is rendered to prose, HTML is parsed for its visible text, LaTeX,
+/// reStructuredText and AsciiDoc markup is stripped, and plain text must be valid UTF-8.
+pub fn extract_document_at(
+    bytes: &[u8],
+    format: Format,
+    path: Option<&Path>,
+    options: &Options,
+) -> Result<Extracted, Box<dyn Error>> {
+    match format {
+        Format::Pdf => Ok(extract_text_from_mem(bytes)?.into()),
+        Format::Docx => Ok(Extracted::from_parts(extract_docx_parts(
+            bytes,
+            &options.docx,
+        )?)),
+        Format::Odt => Ok(Extracted::from_parts(extract_odt_parts(
+            bytes,
+            &options.docx,
+        )?)),
+        Format::Markdown => {
+            let source = std::str::from_utf8(bytes)?;
+            Ok(extract_markdown_text(source, &options.markdown).into())
+        }
+        Format::Epub => Ok(Extracted::from_parts(extract_epub_parts(
+            bytes,
+            &options.html,
+        )?)),
+        Format::Pptx => Ok(Extracted::from_parts(extract_pptx_parts(
+            bytes,
+            &options.pptx,
+        )?)),
+        Format::Xlsx => Ok(Extracted::from_parts(extract_xlsx_parts(
+            bytes,
+            &options.sheets,
+        )?)),
+        Format::Ods => Ok(Extracted::from_parts(extract_ods_parts(
+            bytes,
+            &options.sheets,
+        )?)),
+        Format::Rtf => Ok(extract_rtf_text(bytes)?.into()),
+        Format::Latex => {
+            let source = std::str::from_utf8(bytes)?;
+            Ok(Extracted::from_parts(extract_latex_parts(
+                source,
+                path,
+                &options.latex,
+            )?))
+        }
+        Format::Rst => {
+            let source = std::str::from_utf8(bytes)?;
+            Ok(extract_rst_text(source, path, &options.markup)?.into())
+        }
+        Format::AsciiDoc => {
+            let source = std::str::from_utf8(bytes)?;
+            Ok(extract_asciidoc_text(source, path, &options.markup)?.into())
+        }
+        Format::Html => {
+            let source = std::str::from_utf8(bytes)?;
+            Ok(extract_html_text(source, &options.html)?.into())
+        }
+        Format::Text => Ok(String::from_utf8(bytes.to_vec())?.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_zip_file;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(Format::from_path(Path::new("a/b.pdf")), Format::Pdf);
+        assert_eq!(
+            Format::from_path(Path::new("notes.markdown")),
+            Format::Markdown
+        );
+        assert_eq!(Format::from_path(Path::new("README")), Format::Text);
+        assert_eq!(Format::from_path(Path::new("SPEC.PDF")), Format::Pdf);
+        assert_eq!("DOCX".parse::<Format>(), Ok(Format::Docx));
+        assert!("xls".parse::<Format>().is_err());
+        assert_eq!(Format::sniff(b"%PDF-1.7\n"), Format::Pdf);
+        assert_eq!(Format::sniff(b"PK\x03\x04rest"), Format::Docx);
+        let mut odt = b"PK\x03\x04".to_vec();
+        odt.resize(30, 0);
+        odt.extend_from_slice(b"mimetypeapplication/vnd.oasis.opendocument.text");
+        assert_eq!(Format::sniff(&odt), Format::Odt);
+        odt.truncate(30);
+        odt.extend_from_slice(b"mimetypeapplication/epub+zip");
+        assert_eq!(Format::sniff(&odt), Format::Epub);
+        let dir = TempDir::new().unwrap();
+        let deck = create_zip_file(&dir, "deck.pptx", &[("ppt/presentation.xml", "<p/>")]);
+        assert_eq!(Format::sniff(&fs::read(deck).unwrap()), Format::Pptx);
+        let book = create_zip_file(&dir, "book.xlsx", &[("xl/workbook.xml", "<workbook/>")]);
+        assert_eq!(Format::sniff(&fs::read(book).unwrap()), Format::Xlsx);
+        assert_eq!(Format::from_path(Path::new("Report.ODT")), Format::Odt);
+        assert_eq!(Format::sniff(b"# Title"), Format::Text);
+        assert_eq!(Format::sniff(b"{\\rtf1\\ansi hi}"), Format::Rtf);
+        assert_eq!(Format::sniff(b"\n<!DOCTYPE HTML>"), Format::Html);
+        assert_eq!(Format::from_path(Path::new("index.htm")), Format::Html);
+        assert_eq!(Format::from_path(Path::new("thesis.tex")), Format::Latex);
+        assert_eq!("latex".parse::<Format>(), Ok(Format::Latex));
+        assert_eq!(Format::from_path(Path::new("index.rst")), Format::Rst);
+        assert_eq!(Format::from_path(Path::new("guide.adoc")), Format::AsciiDoc);
+    }
+
+    #[test]
+    fn test_invalid_utf8_text() {
+        let result = extract_bytes(&[0xff, 0xfe, 0x00], Format::Text, &Options::default());
+        assert!(result.is_err());
+    }
+}
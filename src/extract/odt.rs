@@ -0,0 +1,274 @@
+use std::error::Error;
+use std::io::Cursor;
+
+use quick_xml::events::Event;
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::NsReader;
+use zip::ZipArchive;
+
+use crate::extract::package::{attribute, read_entry};
+use crate::extract::{DocxOptions, Part};
+
+/// The parts of an OpenDocument text, in report order. They use the same names as the DOCX
+/// parts so one set of include flags serves both formats.
+const PARTS: [&str; 6] = [
+    "body",
+    "headers",
+    "footers",
+    "footnotes",
+    "endnotes",
+    "comments",
+];
+const BODY: usize = 0;
+const HEADERS: usize = 1;
+const FOOTERS: usize = 2;
+const FOOTNOTES: usize = 3;
+const ENDNOTES: usize = 4;
+const COMMENTS: usize = 5;
+
+/// The OpenDocument namespaces whose elements shape the text. Elements are matched by
+/// namespace rather than prefix, since a document may bind these to any prefix.
+const OFFICE_NS: &[u8] = b"urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+const STYLE_NS: &[u8] = b"urn:oasis:names:tc:opendocument:xmlns:style:1.0";
+const TEXT_NS: &[u8] = b"urn:oasis:names:tc:opendocument:xmlns:text:1.0";
+const SVG_NS: &[u8] = b"urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0";
+const DC_NS: &[u8] = b"http://purl.org/dc/elements/1.1/";
+
+/// Extracts the body text of an ODT file from its "content.xml", leaving out notes and
+/// comments.
+pub fn extract_odt_text(bytes: &[u8]) -> Result<String, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut texts: [String; 6] = Default::default();
+    odf_text(
+        &read_entry(&mut archive, "content.xml")?,
+        Some(BODY),
+        &mut texts,
+    )?;
+    Ok(std::mem::take(&mut texts[BODY]))
+}
+
+/// Extracts every part of an ODT file that has text: the body, headers and footers (from the
+/// master pages in "styles.xml"), footnotes, endnotes and comments (`office:annotation`). Parts
+/// are selected with the same [`DocxOptions`] as Word documents.
+pub fn extract_odt_parts(bytes: &[u8], options: &DocxOptions) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut texts: [String; 6] = Default::default();
+    odf_text(
+        &read_entry(&mut archive, "content.xml")?,
+        Some(BODY),
+        &mut texts,
+    )?;
+    if archive.by_name("styles.xml").is_ok() {
+        odf_text(&read_entry(&mut archive, "styles.xml")?, None, &mut texts)?;
+    }
+
+    Ok(PARTS
+        .iter()
+        .zip(texts)
+        .enumerate()
+        .filter(|(index, (_, text))| *index == BODY || !text.trim().is_empty())
+        .map(|(_, (name, text))| Part {
+            name: name.to_string(),
+            text,
+            included: options.includes(name),
+        })
+        .collect())
+}
+
+/// Walks an OpenDocument XML file, appending its text to the part it belongs to. Text outside
+/// notes, comments, headers and footers goes to `default_part`, or is dropped when that is
+/// `None` (as for the styles in "styles.xml").
+///
+/// Spans are inline, so a word split across `text:span` elements stays whole. Paragraphs and
+/// headings end with a line break, `text:s`, `text:tab` and `text:line-break` become
+/// whitespace, and entities are decoded. Note citations (the footnote numbers), the deleted
+/// text kept in `text:tracked-changes`, comment authors and dates, and image titles and
+/// descriptions are skipped.
+fn odf_text(
+    xml: &str,
+    default_part: Option<usize>,
+    texts: &mut [String; 6],
+) -> Result<(), Box<dyn Error>> {
+    let mut reader = NsReader::from_str(xml);
+    let mut skip_depth = 0usize;
+    // The part of each open note, comment, header or footer, innermost last.
+    let mut targets: Vec<Option<usize>> = Vec::new();
+
+    loop {
+        let current = targets.last().copied().unwrap_or(default_part);
+        let (namespace, event) = reader.read_resolved_event()?;
+        let namespace = match namespace {
+            ResolveResult::Bound(Namespace(namespace)) => namespace,
+            _ => &[],
+        };
+        match event {
+            Event::Start(e) => match (namespace, e.local_name().as_ref()) {
+                (TEXT_NS, b"tracked-changes" | b"note-citation")
+                | (DC_NS, b"creator" | b"date")
+                | (SVG_NS, b"title" | b"desc") => skip_depth += 1,
+                (TEXT_NS, b"note") => targets.push(match attribute(&e, b"note-class").as_deref() {
+                    Some("endnote") => Some(ENDNOTES),
+                    _ => Some(FOOTNOTES),
+                }),
+                (OFFICE_NS, b"annotation") => targets.push(Some(COMMENTS)),
+                (STYLE_NS, b"header" | b"header-left" | b"header-first") => {
+                    targets.push(Some(HEADERS))
+                }
+                (STYLE_NS, b"footer" | b"footer-left" | b"footer-first") => {
+                    targets.push(Some(FOOTERS))
+                }
+                _ => {}
+            },
+            Event::End(e) => match (namespace, e.local_name().as_ref()) {
+                (TEXT_NS, b"tracked-changes" | b"note-citation")
+                | (DC_NS, b"creator" | b"date")
+                | (SVG_NS, b"title" | b"desc") => skip_depth = skip_depth.saturating_sub(1),
+                (TEXT_NS, b"note")
+                | (OFFICE_NS, b"annotation")
+                | (
+                    STYLE_NS,
+                    b"header" | b"header-left" | b"header-first" | b"footer" | b"footer-left"
+                    | b"footer-first",
+                ) => {
+                    targets.pop();
+                }
+                (TEXT_NS, b"p" | b"h") => {
+                    if let Some(part) = current {
+                        texts[part].push('\n');
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(e) => {
+                let (Some(part), 0) = (current, skip_depth) else {
+                    continue;
+                };
+                match (namespace, e.local_name().as_ref()) {
+                    (TEXT_NS, b"s") => texts[part].push(' '),
+                    (TEXT_NS, b"tab") => texts[part].push('\t'),
+                    (TEXT_NS, b"line-break") => texts[part].push('\n'),
+                    _ => {}
+                }
+            }
+            Event::Text(e) => {
+                if let (Some(part), 0) = (current, skip_depth) {
+                    texts[part].push_str(&e.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_zip_file;
+    use crate::{tokenize, Tokenizer};
+    use tempfile::TempDir;
+
+    const CONTENT: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <office:document-content xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+        xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+        xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\
+        <office:body><office:text>\
+        <text:tracked-changes><text:changed-region text:id=\"c1\"><text:deletion>\
+        <text:p>deleted words</text:p></text:deletion></text:changed-region></text:tracked-changes>\
+        <text:h text:outline-level=\"1\">Intro</text:h>\
+        <text:p>Split<text:span text:style-name=\"T1\">ting</text:span> one<text:s/>two<text:tab/>three<text:line-break/>four\
+        <text:note text:id=\"n1\" text:note-class=\"footnote\"><text:note-citation>1</text:note-citation>\
+        <text:note-body><text:p>A footnote</text:p></text:note-body></text:note>\
+        &amp; five</text:p>\
+        <text:p>Six<office:annotation><dc:creator>Reviewer Name</dc:creator><dc:date>2024-01-01</dc:date>\
+        <text:p>Check this</text:p></office:annotation></text:p>\
+        <text:p><text:note text:note-class=\"endnote\"><text:note-citation>i</text:note-citation>\
+        <text:note-body><text:p>Closing remark</text:p></text:note-body></text:note></text:p>\
+        </office:text></office:body></office:document-content>";
+
+    const STYLES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <office:document-styles xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+        xmlns:style=\"urn:oasis:names:tc:opendocument:xmlns:style:1.0\" \
+        xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\">\
+        <office:styles><style:style style:name=\"Standard\"/></office:styles>\
+        <office:master-styles><style:master-page style:name=\"Standard\">\
+        <style:header><text:p>Running title</text:p></style:header>\
+        <style:footer><text:p>Page</text:p></style:footer>\
+        </style:master-page></office:master-styles></office:document-styles>";
+
+    fn odt_bytes(dir: &TempDir) -> Vec<u8> {
+        let path = create_zip_file(
+            dir,
+            "doc.odt",
+            &[("content.xml", CONTENT), ("styles.xml", STYLES)],
+        );
+        std::fs::read(path).unwrap()
+    }
+
+    fn words(text: &str) -> Vec<String> {
+        tokenize(text, Tokenizer::Legacy)
+    }
+
+    #[test]
+    fn test_odt_body_text() {
+        let dir = TempDir::new().unwrap();
+        let text = extract_odt_text(&odt_bytes(&dir)).unwrap();
+        assert_eq!(
+            words(&text),
+            vec![
+                "intro",
+                "splitting",
+                "one",
+                "two",
+                "three",
+                "four",
+                "five",
+                "six"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_odt_parts() {
+        let dir = TempDir::new().unwrap();
+        let options = DocxOptions {
+            footnotes: true,
+            ..DocxOptions::default()
+        };
+        let parts = extract_odt_parts(&odt_bytes(&dir), &options).unwrap();
+        let summary: Vec<(&str, Vec<String>, bool)> = parts
+            .iter()
+            .map(|part| (part.name.as_str(), words(&part.text), part.included))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "body",
+                    words("intro splitting one two three four five six"),
+                    true
+                ),
+                ("headers", words("running title"), false),
+                ("footers", words("page"), false),
+                ("footnotes", words("a footnote"), true),
+                ("endnotes", words("closing remark"), false),
+                ("comments", words("check this"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_odf_text_matches_namespaces_not_prefixes() {
+        let xml = "<doc xmlns:t=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+            xmlns:o=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+            xmlns:svg=\"urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0\">\
+            <t:p>one<t:s/>two<o:annotation><t:p>remark</t:p></o:annotation></t:p>\
+            <t:p><t:title>Report</t:title><svg:title>hidden</svg:title></t:p></doc>";
+        let mut texts: [String; 6] = Default::default();
+        odf_text(xml, Some(BODY), &mut texts).unwrap();
+        assert_eq!(words(&texts[BODY]), words("one two report"));
+        assert_eq!(words(&texts[COMMENTS]), words("remark"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers shared by the extractors for ZIP-packaged XML formats (DOCX, ODT and friends).
+
+use std::error::Error;
+use std::io::{Read, Seek};
+
+use quick_xml::events::BytesStart;
+use zip::ZipArchive;
+
+/// Names of the archive entries `<prefix>.xml` and `<prefix><N>.xml`, sorted by `N`.
+pub fn numbered_entries<R: Read + Seek>(archive: &ZipArchive<R>, prefix: &str) -> Vec<String> {
+    let mut entries: Vec<(usize, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let number = name.strip_prefix(prefix)?.strip_suffix(".xml")?;
+            if number.is_empty() {
+                Some((0, name.to_string()))
+            } else {
+                number.parse().ok().map(|n| (n, name.to_string()))
+            }
+        })
+        .collect();
+    entries.sort();
+    entries.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Reads an archive entry as UTF-8 text.
+pub fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<String, Box<dyn Error>> {
+    let mut entry = archive.by_name(name)?;
+    let mut xml_content = String::new();
+    entry.read_to_string(&mut xml_content)?;
+    Ok(xml_content)
+}
+
+/// Returns the value of the attribute with the given local name (ignoring its prefix).
+pub fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok().map(|value| value.into_owned()))
+}
+
+/// Resolves an `href` found in the archive entry `base` to an archive path, dropping any
+/// fragment and decoding percent-escapes.
+pub fn resolve(base: &str, href: &str) -> String {
+    let href = percent_decode(href.split('#').next().unwrap_or_default());
+    let mut segments: Vec<&str> = match base.rsplit_once('/') {
+        Some((dir, _)) if !href.starts_with('/') => dir.split('/').collect(),
+        _ => Vec::new(),
+    };
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve("OEBPS/content.opf", "text/a.xhtml"),
+            "OEBPS/text/a.xhtml"
+        );
+        assert_eq!(
+            resolve("OEBPS/nav/toc.xhtml", "../a%20b.xhtml#x"),
+            "OEBPS/a b.xhtml"
+        );
+        assert_eq!(resolve("content.opf", "./a.xhtml"), "a.xhtml");
+    }
+}
@@ -0,0 +1,275 @@
+use std::error::Error;
+use std::io::{Cursor, Read, Seek};
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use zip::ZipArchive;
+
+use crate::extract::package::{attribute, numbered_entries, read_entry, resolve};
+use crate::extract::Part;
+
+/// The relationship type linking a slide to its speaker notes.
+const NOTES_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide";
+
+/// Selects which parts of a PowerPoint deck count towards its totals and how they are listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PptxOptions {
+    /// Count the text on the slides themselves.
+    pub slides: bool,
+    /// Count the speaker notes.
+    pub notes: bool,
+    /// List every slide (and its notes) as a separate part instead of one `slides` and one
+    /// `notes` part for the whole deck.
+    pub per_slide: bool,
+}
+
+impl Default for PptxOptions {
+    fn default() -> Self {
+        PptxOptions {
+            slides: true,
+            notes: false,
+            per_slide: false,
+        }
+    }
+}
+
+impl PptxOptions {
+    /// Includes or excludes a part by its command-line name (`slides` or `notes`).
+    pub fn set(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        match name {
+            "slides" => self.slides = enabled,
+            "notes" => self.notes = enabled,
+            _ => return Err(format!("Unknown PPTX part '{}'", name)),
+        }
+        Ok(())
+    }
+}
+
+/// Extracts the slide text and speaker notes of a PPTX file, slide by slide in the order of
+/// their `ppt/slides/slideN.xml` numbers. By default the deck is reported as a `slides` and a
+/// `notes` part; with `per_slide` each slide gets its own `slide N` part, followed by a
+/// `notes N` part if it has notes.
+pub fn extract_pptx_parts(
+    bytes: &[u8],
+    options: &PptxOptions,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let slides = numbered_entries(&archive, "ppt/slides/slide");
+    if slides.is_empty() {
+        return Err("No slides found in the PPTX archive".into());
+    }
+
+    let mut parts = Vec::new();
+    let mut slide_text = String::new();
+    let mut notes_text = String::new();
+    for (index, slide) in slides.iter().enumerate() {
+        let text = drawing_text(&read_entry(&mut archive, slide)?)?;
+        let notes = match notes_entry(&mut archive, slide)? {
+            Some(entry) => drawing_text(&read_entry(&mut archive, &entry)?)?,
+            None => String::new(),
+        };
+
+        if options.per_slide {
+            parts.push(Part {
+                name: format!("slide {}", index + 1),
+                text,
+                included: options.slides,
+            });
+            if !notes.trim().is_empty() {
+                parts.push(Part {
+                    name: format!("notes {}", index + 1),
+                    text: notes,
+                    included: options.notes,
+                });
+            }
+        } else {
+            slide_text.push_str(&text);
+            notes_text.push_str(&notes);
+        }
+    }
+
+    if !options.per_slide {
+        parts.push(Part {
+            name: "slides".to_string(),
+            text: slide_text,
+            included: options.slides,
+        });
+        if !notes_text.trim().is_empty() {
+            parts.push(Part {
+                name: "notes".to_string(),
+                text: notes_text,
+                included: options.notes,
+            });
+        }
+    }
+
+    Ok(parts)
+}
+
+/// Finds the notes slide of a slide through the slide's relationships file
+/// (`ppt/slides/_rels/slideN.xml.rels`); notes slides are not numbered like their slides.
+fn notes_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    slide: &str,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let (dir, name) = slide.rsplit_once('/').unwrap_or(("", slide));
+    let rels = format!("{}/_rels/{}.rels", dir, name);
+    if archive.by_name(&rels).is_err() {
+        return Ok(None);
+    }
+
+    let xml = read_entry(archive, &rels)?;
+    let mut reader = Reader::from_str(&xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e)
+                if e.local_name().as_ref() == b"Relationship"
+                    && attribute(&e, b"Type").as_deref() == Some(NOTES_RELATIONSHIP) =>
+            {
+                if let Some(target) = attribute(&e, b"Target") {
+                    let entry = resolve(slide, &target);
+                    return Ok(archive.by_name(&entry).is_ok().then_some(entry));
+                }
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// Collects the DrawingML text of a slide or notes slide: the runs (`a:t`) of every text box,
+/// placeholder and table cell, with a line break after each paragraph. Slide number and date
+/// fields are skipped.
+fn drawing_text(xml: &str) -> Result<String, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_text = false;
+    let mut field_depth = 0usize;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"t" => in_text = true,
+                b"fld" => {
+                    let kind = attribute(&e, b"type").unwrap_or_default();
+                    if kind == "slidenum" || kind.starts_with("datetime") {
+                        field_depth += 1;
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"fld" => field_depth = field_depth.saturating_sub(1),
+                b"p" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"br" => text.push('\n'),
+            Event::Text(e) if in_text && field_depth == 0 => text.push_str(&e.unescape()?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_zip_file;
+    use tempfile::TempDir;
+
+    fn slide(paragraphs: &str) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <p:sld xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
+            xmlns:p=\"http://schemas.openxmlformats.org/presentationml/2006/main\">\
+            <p:cSld><p:spTree><p:sp><p:txBody>{}</p:txBody></p:sp></p:spTree></p:cSld></p:sld>",
+            paragraphs
+        )
+    }
+
+    fn rels(target: &str) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+            <Relationship Id=\"rId1\" Type=\"{}\" Target=\"{}\"/></Relationships>",
+            NOTES_RELATIONSHIP, target
+        )
+    }
+
+    fn deck(dir: &TempDir) -> Vec<u8> {
+        let slide1 = slide(
+            "<a:p><a:r><a:t>Quarterly</a:t></a:r><a:r><a:t> results &amp; plans</a:t></a:r></a:p>\
+            <a:p><a:r><a:t>Line</a:t></a:r><a:br/><a:r><a:t>break</a:t></a:r>\
+            <a:fld id=\"{1}\" type=\"slidenum\"><a:t>1</a:t></a:fld></a:p>",
+        );
+        let notes1 = slide("<a:p><a:r><a:t>Mention the budget</a:t></a:r></a:p>");
+        let slide2 = slide("<a:p><a:r><a:t>Questions</a:t></a:r></a:p>");
+        let slide10 = slide("<a:p><a:r><a:t>Appendix slide</a:t></a:r></a:p>");
+        let path = create_zip_file(
+            dir,
+            "deck.pptx",
+            &[
+                ("ppt/slides/slide10.xml", &slide10),
+                ("ppt/slides/slide2.xml", &slide2),
+                ("ppt/slides/slide1.xml", &slide1),
+                (
+                    "ppt/slides/_rels/slide1.xml.rels",
+                    &rels("../notesSlides/notesSlide7.xml"),
+                ),
+                ("ppt/notesSlides/notesSlide7.xml", &notes1),
+            ],
+        );
+        std::fs::read(path).unwrap()
+    }
+
+    fn word_counts(parts: &[Part]) -> Vec<(&str, usize, bool)> {
+        parts
+            .iter()
+            .map(|part| {
+                (
+                    part.name.as_str(),
+                    part.text.split_whitespace().count(),
+                    part.included,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_slides_and_notes() {
+        let dir = TempDir::new().unwrap();
+        let parts = extract_pptx_parts(&deck(&dir), &PptxOptions::default()).unwrap();
+        assert_eq!(
+            word_counts(&parts),
+            vec![("slides", 9, true), ("notes", 3, false)]
+        );
+        assert!(parts[0]
+            .text
+            .starts_with("Quarterly results & plans\nLine\nbreak\nQuestions"));
+    }
+
+    #[test]
+    fn test_per_slide_breakdown() {
+        let dir = TempDir::new().unwrap();
+        let mut options = PptxOptions {
+            per_slide: true,
+            ..PptxOptions::default()
+        };
+        options.set("notes", true).unwrap();
+        let parts = extract_pptx_parts(&deck(&dir), &options).unwrap();
+        assert_eq!(
+            word_counts(&parts),
+            vec![
+                ("slide 1", 6, true),
+                ("notes 1", 3, true),
+                ("slide 2", 1, true),
+                ("slide 3", 2, true),
+            ]
+        );
+        assert!(options.set("handouts", true).is_err());
+    }
+}
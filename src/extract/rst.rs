@@ -0,0 +1,471 @@
+use std::error::Error;
+use std::path::Path;
+
+use regex::{Captures, Regex};
+
+use crate::extract::include::{base_dir, read_include};
+use crate::extract::markup::MarkupOptions;
+
+/// Directives whose arguments and body are prose, such as admonitions, with the arguments
+/// (a title or the first line of the text) counted.
+const TITLED_DIRECTIVES: &[&str] = &[
+    "admonition",
+    "attention",
+    "caution",
+    "csv-table",
+    "danger",
+    "deprecated",
+    "error",
+    "hint",
+    "important",
+    "list-table",
+    "note",
+    "rubric",
+    "seealso",
+    "sidebar",
+    "table",
+    "tip",
+    "topic",
+    "versionadded",
+    "versionchanged",
+    "warning",
+];
+
+/// Directives whose body is prose but whose arguments are not (a path, a class or an
+/// expression).
+const BODY_DIRECTIVES: &[&str] = &[
+    "centered",
+    "class",
+    "compound",
+    "container",
+    "epigraph",
+    "figure",
+    "glossary",
+    "highlights",
+    "hlist",
+    "only",
+    "pull-quote",
+];
+
+/// Directives whose body is a literal block.
+const LITERAL_DIRECTIVES: &[&str] = &[
+    "code",
+    "code-block",
+    "parsed-literal",
+    "productionlist",
+    "sourcecode",
+];
+
+/// How the lines after a directive or `::` marker are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    /// Ordinary text.
+    None,
+    /// Lines indented deeper than the given level are dropped (comments, targets, directives
+    /// that hold no prose).
+    Skip(usize),
+    /// Lines indented deeper than the given level are a literal block.
+    Literal(usize),
+    /// A directive's option fields (`:name: value`), dropped before its body starts.
+    Options { level: usize, literal: bool },
+}
+
+/// Extracts the prose of a reStructuredText document. Section adornments, table borders,
+/// comments, hyperlink targets and substitution definitions are dropped, as are directives
+/// that hold no prose (`image`, `toctree`, `math`, ...) and the option fields of those that
+/// do. Admonitions, figures and tables keep their text. Roles and references count as their
+/// displayed text, and footnote references are dropped. `.. include::` is followed relative to
+/// the directory of `path`, or of the current directory when there is none.
+pub fn extract_rst_text(
+    source: &str,
+    path: Option<&Path>,
+    options: &MarkupOptions,
+) -> Result<String, Box<dyn Error>> {
+    let reader = RstReader::new(options);
+    let mut text = String::new();
+    reader.process(source, &base_dir(path), 0, &mut text)?;
+    Ok(text)
+}
+
+struct RstReader<'a> {
+    options: &'a MarkupOptions,
+    inline_literal: Regex,
+    emphasis: Regex,
+    role: Regex,
+    suffix_role: Regex,
+    reference: Regex,
+    interpreted: Regex,
+    footnote_reference: Regex,
+    substitution: Regex,
+    named_reference: Regex,
+    list_marker: Regex,
+    field: Regex,
+}
+
+impl<'a> RstReader<'a> {
+    fn new(options: &'a MarkupOptions) -> Self {
+        RstReader {
+            options,
+            inline_literal: Regex::new(r"``(.+?)``").unwrap(),
+            emphasis: Regex::new(r"\*\*?([^*\s](?:[^*]*[^*\s])?)\*\*?").unwrap(),
+            role: Regex::new(r":([\w:+.-]+):`([^`]*)`").unwrap(),
+            suffix_role: Regex::new(r"`([^`]*)`:[\w:+.-]+:").unwrap(),
+            reference: Regex::new(r"`([^`<]*?)\s*(?:<[^>`]*>)?`__?").unwrap(),
+            interpreted: Regex::new(r"`([^`]+)`").unwrap(),
+            footnote_reference: Regex::new(r"\[(?:#[\w-]*|\*|\d+|[\w.-]+)\]_").unwrap(),
+            substitution: Regex::new(r"\|[^|\s][^|]*\|_{0,2}").unwrap(),
+            named_reference: Regex::new(r"(\w)__?([\s.,;:!?)]|$)").unwrap(),
+            list_marker: Regex::new(r"^(?:[-*+•‣⁃]|#\.|\(?(?:\d+|[a-zA-Z]|#)[.)])\s+").unwrap(),
+            field: Regex::new(r"^:[^:`\s][^:`]*:(?:\s+|$)").unwrap(),
+        }
+    }
+
+    /// Appends the prose of `source` to `text`, resolving includes against `dir`.
+    fn process(
+        &self,
+        source: &str,
+        dir: &Path,
+        depth: usize,
+        text: &mut String,
+    ) -> Result<(), Box<dyn Error>> {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut block = Block::None;
+        // Set by a paragraph ending in `::`: an indented block after it is literal.
+        let mut pending_literal: Option<usize> = None;
+
+        for (index, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            if self.block_line(&mut block, indent, trimmed, text) {
+                continue;
+            }
+            if trimmed.is_empty() {
+                text.push('\n');
+                continue;
+            }
+            if let Some(level) = pending_literal.take() {
+                if indent > level {
+                    block = Block::Literal(level);
+                    self.block_line(&mut block, indent, trimmed, text);
+                    continue;
+                }
+            }
+
+            if trimmed == ".." || trimmed.starts_with(".. ") {
+                block = self.explicit_markup(&lines[index..], indent, dir, depth, text)?;
+            } else if trimmed.starts_with(">>>") {
+                // A doctest block.
+                if self.options.literal_blocks {
+                    text.push_str(trimmed);
+                    text.push('\n');
+                }
+            } else if trimmed == "::" {
+                pending_literal = Some(indent);
+            } else if !is_adornment(trimmed) {
+                let mut line = trimmed;
+                if let Some(content) = line.strip_suffix("::") {
+                    pending_literal = Some(indent);
+                    line = match content.strip_suffix(char::is_whitespace) {
+                        Some(content) => content,
+                        // `Example::` reads as `Example:`.
+                        None => &line[..line.len() - 1],
+                    };
+                }
+                self.push_text(line, text);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a line inside a directive body, comment or literal block, returning whether it
+    /// was consumed. Leaves the block once a line is indented no deeper than its level.
+    fn block_line(
+        &self,
+        block: &mut Block,
+        indent: usize,
+        trimmed: &str,
+        text: &mut String,
+    ) -> bool {
+        loop {
+            match *block {
+                Block::None => return false,
+                Block::Skip(level) if trimmed.is_empty() || indent > level => return true,
+                Block::Literal(level) if trimmed.is_empty() || indent > level => {
+                    if self.options.literal_blocks {
+                        text.push_str(trimmed);
+                        text.push('\n');
+                    }
+                    return true;
+                }
+                Block::Options { level, .. }
+                    if !trimmed.is_empty() && indent > level && trimmed.starts_with(':') =>
+                {
+                    return true;
+                }
+                Block::Options { level, literal } => {
+                    *block = if literal {
+                        Block::Literal(level)
+                    } else {
+                        Block::None
+                    };
+                }
+                Block::Skip(_) | Block::Literal(_) => *block = Block::None,
+            }
+        }
+    }
+
+    /// Handles an explicit markup block (`.. ` line) at the start of `lines`, returning how the
+    /// lines after it are read.
+    fn explicit_markup(
+        &self,
+        lines: &[&str],
+        indent: usize,
+        dir: &Path,
+        depth: usize,
+        text: &mut String,
+    ) -> Result<Block, Box<dyn Error>> {
+        let rest = lines[0].trim()[2..].trim();
+
+        // A footnote or citation keeps its text; its label is dropped.
+        if let Some(label) = rest.strip_prefix('[') {
+            if let Some((_, content)) = label.split_once(']') {
+                self.push_text(content.trim(), text);
+                return Ok(Block::None);
+            }
+        }
+
+        let Some((name, arguments)) = directive(rest) else {
+            // Comments, hyperlink targets (`.. _name:`) and substitution definitions
+            // (`.. |name| image:: ...`).
+            return Ok(Block::Skip(indent));
+        };
+
+        if name == "include" {
+            let literal = lines[1..]
+                .iter()
+                .take_while(|line| {
+                    let trimmed = line.trim();
+                    line.len() - line.trim_start().len() > indent && trimmed.starts_with(':')
+                })
+                .any(|line| {
+                    let trimmed = line.trim();
+                    trimmed.starts_with(":literal:") || trimmed.starts_with(":code:")
+                });
+            let (source, include_dir) = read_include(dir, arguments, depth)?;
+            if !literal {
+                self.process(&source, &include_dir, depth + 1, text)?;
+            } else if self.options.literal_blocks {
+                text.push_str(&source);
+                text.push('\n');
+            }
+            return Ok(Block::Skip(indent));
+        }
+
+        if LITERAL_DIRECTIVES.contains(&name.as_str()) {
+            return Ok(Block::Options {
+                level: indent,
+                literal: true,
+            });
+        }
+        if TITLED_DIRECTIVES.contains(&name.as_str()) {
+            self.push_text(arguments, text);
+        } else if !BODY_DIRECTIVES.contains(&name.as_str()) {
+            return Ok(Block::Skip(indent));
+        }
+        Ok(Block::Options {
+            level: indent,
+            literal: false,
+        })
+    }
+
+    /// Appends a line of prose with its list marker, field name and inline markup removed.
+    fn push_text(&self, line: &str, text: &mut String) {
+        let mut line = line;
+        let table_row = line.len() > 1 && line.starts_with('|') && line.ends_with('|');
+        if !table_row {
+            // A line block (`| text`).
+            line = line.strip_prefix("| ").unwrap_or(line);
+        }
+        let line = self.list_marker.replace(line, "");
+        let line = self.field.replace(&line, "");
+
+        let line = self
+            .inline_literal
+            .replace_all(&line, |caps: &Captures| self.literal(&caps[1]).to_string());
+        let line = self.role.replace_all(&line, |caps: &Captures| {
+            let content = displayed(&caps[2]);
+            match caps[1].rsplit(':').next().unwrap_or_default() {
+                "math" => " ".to_string(),
+                "code" | "literal" | "samp" => self.literal(content).to_string(),
+                _ => content.to_string(),
+            }
+        });
+        let line = self.suffix_role.replace_all(&line, "$1");
+        let line = self.emphasis.replace_all(&line, "$1");
+        let line = self.reference.replace_all(&line, "$1");
+        let line = self.interpreted.replace_all(&line, "$1");
+        let line = self.footnote_reference.replace_all(&line, "");
+        let line = self.substitution.replace_all(&line, "");
+        let line = self.named_reference.replace_all(&line, "$1$2");
+
+        if table_row {
+            text.push_str(&line.replace('|', " "));
+        } else {
+            text.push_str(&line);
+        }
+        text.push('\n');
+    }
+
+    fn literal<'t>(&self, content: &'t str) -> &'t str {
+        if self.options.inline_literals {
+            content
+        } else {
+            " "
+        }
+    }
+}
+
+/// Splits a directive (`name:: arguments`) into its lowercase name and arguments.
+fn directive(rest: &str) -> Option<(String, &str)> {
+    let (name, arguments) = rest.split_once("::")?;
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_:+.".contains(c));
+    valid.then(|| (name.to_ascii_lowercase(), arguments.trim()))
+}
+
+/// The displayed text of a role or reference: `title` for `title <target>`.
+fn displayed(content: &str) -> &str {
+    match content.trim_end().strip_suffix('>') {
+        Some(rest) => rest
+            .rsplit_once('<')
+            .map_or(content, |(title, _)| title.trim()),
+        None => content,
+    }
+}
+
+/// Whether a line is a section adornment, transition or table border, such as `=====`,
+/// `===  ====` or `+----+----+`.
+fn is_adornment(line: &str) -> bool {
+    let mut chars = line.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if first == '+' {
+        return line.len() > 1 && line.chars().all(|c| "+-=|: ".contains(c));
+    }
+    first.is_ascii_punctuation() && line.len() > 1 && line.chars().all(|c| c == first || c == ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_file;
+    use tempfile::TempDir;
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn test_rst_text() {
+        let source = "\
+=====
+Title
+=====
+
+.. meta::
+   :keywords: hidden
+
+Intro with *emphasis*, ``code``, :ref:`a label <target>` and a `link <https://x.org>`_.
+See :math:`x^2` and the guide_ [#]_.
+
+.. _guide: https://example.com
+
+.. note:: Mind
+   the gap.
+
+.. code-block:: python
+   :linenos:
+
+   print(\"skipped\")
+
+Example::
+
+    literal text
+
+- First item
+- Second item
+
+.. image:: picture.png
+   :alt: ignored
+
+.. This is a comment
+   over two lines.
+
++-----+-----+
+| One | Two |
++-----+-----+
+
+.. [#] Footnote text.
+";
+        let text = extract_rst_text(source, None, &MarkupOptions::default()).unwrap();
+        assert_eq!(
+            words(&text),
+            vec![
+                "Title",
+                "Intro",
+                "with",
+                "emphasis,",
+                "code,",
+                "a",
+                "label",
+                "and",
+                "a",
+                "link.",
+                "See",
+                "and",
+                "the",
+                "guide",
+                ".",
+                "Mind",
+                "the",
+                "gap.",
+                "Example:",
+                "First",
+                "item",
+                "Second",
+                "item",
+                "One",
+                "Two",
+                "Footnote",
+                "text."
+            ]
+        );
+
+        let options = MarkupOptions {
+            literal_blocks: true,
+            inline_literals: false,
+        };
+        let text = extract_rst_text(source, None, &options).unwrap();
+        assert!(text.contains("print(\"skipped\")") && text.contains("literal text"));
+        assert!(!text.contains("code"));
+    }
+
+    #[test]
+    fn test_rst_include() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("parts")).unwrap();
+        let main = create_test_file(&dir, "index.rst", "Main\n\n.. include:: parts/one.rst\n");
+        create_test_file(&dir, "parts/one.rst", "Included\n\n.. include:: two.rst\n");
+        create_test_file(&dir, "parts/two.rst", "Nested text\n");
+
+        let source = std::fs::read_to_string(&main).unwrap();
+        let options = MarkupOptions::default();
+        let text = extract_rst_text(&source, Some(Path::new(&main)), &options).unwrap();
+        assert_eq!(words(&text), vec!["Main", "Included", "Nested", "text"]);
+        assert!(extract_rst_text(".. include:: missing.rst", None, &options).is_err());
+        assert!(options.clone().set("roles", true).is_err());
+    }
+}
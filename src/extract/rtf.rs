@@ -0,0 +1,269 @@
+use std::error::Error;
+
+/// Destinations whose contents are never document text: tables, metadata, embedded pictures and
+/// objects, field instructions and bookmark names.
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "bkmkend",
+    "bkmkstart",
+    "colortbl",
+    "colorschememapping",
+    "datastore",
+    "filetbl",
+    "fldinst",
+    "fonttbl",
+    "generator",
+    "info",
+    "latentstyles",
+    "listoverridetable",
+    "listtable",
+    "objdata",
+    "pgdsctbl",
+    "pict",
+    "revtbl",
+    "rsidtbl",
+    "stylesheet",
+    "themedata",
+    "xmlnstbl",
+];
+
+/// The state a group (`{...}`) inherits from its parent.
+#[derive(Debug, Clone, Copy)]
+struct GroupState {
+    /// Inside a destination whose text is dropped.
+    skip: bool,
+    /// How many fallback characters follow a `\uN` escape (set by `\ucN`).
+    unicode_skip: usize,
+}
+
+/// Extracts the text of an RTF document. Control words are interpreted rather than counted:
+/// paragraph, line, row and page breaks become line breaks, cells and tabs become whitespace,
+/// `\uN` and `\'hh` escapes are decoded (the latter as Windows-1252), and optional `\*`
+/// destinations, font, colour and style tables, document information, pictures and embedded
+/// objects are skipped. Fields count as their displayed result.
+pub fn extract_rtf_text(bytes: &[u8]) -> Result<String, Box<dyn Error>> {
+    if !bytes.starts_with(b"{\\rtf") {
+        return Err("Not an RTF document (missing {\\rtf header)".into());
+    }
+
+    let mut text = String::new();
+    let mut state = GroupState {
+        skip: false,
+        unicode_skip: 1,
+    };
+    let mut stack: Vec<GroupState> = Vec::new();
+    // Fallback characters still to drop after a `\uN` escape.
+    let mut pending_skip = 0usize;
+    // Set by `\*`: the next control word names an optional destination.
+    let mut optional_destination = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => {
+                stack.push(state);
+                pending_skip = 0;
+                i += 1;
+            }
+            b'}' => {
+                state = stack.pop().unwrap_or(state);
+                pending_skip = 0;
+                i += 1;
+            }
+            b'\r' | b'\n' => i += 1,
+            b'\\' => {
+                let (control, next) = read_control(bytes, i + 1);
+                i = next;
+                match control {
+                    Control::Symbol(b'*') => optional_destination = true,
+                    Control::Symbol(b'\'') => {
+                        let byte = bytes
+                            .get(i..i + 2)
+                            .and_then(|hex| std::str::from_utf8(hex).ok())
+                            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                        if let Some(byte) = byte {
+                            i += 2;
+                            emit(&mut text, &state, &mut pending_skip, windows_1252(byte));
+                        }
+                    }
+                    Control::Symbol(symbol) => {
+                        let c = match symbol {
+                            b'~' => Some(' '),
+                            b'_' => Some('-'),
+                            b'\n' | b'\r' => Some('\n'),
+                            b'\\' | b'{' | b'}' => Some(symbol as char),
+                            // `\-` (optional hyphen) and `\|`, `\:` (formula and index
+                            // characters) have no visible text.
+                            _ => None,
+                        };
+                        if let Some(c) = c {
+                            emit(&mut text, &state, &mut pending_skip, c);
+                        }
+                    }
+                    Control::Word(word, _)
+                        if optional_destination
+                            || SKIPPED_DESTINATIONS.contains(&word.as_str()) =>
+                    {
+                        optional_destination = false;
+                        state.skip = true;
+                    }
+                    Control::Word(word, parameter) => match word.as_str() {
+                        // Raw binary data follows; skip it without interpreting it.
+                        "bin" => i += parameter.unwrap_or(0).max(0) as usize,
+                        "uc" => state.unicode_skip = parameter.unwrap_or(1).max(0) as usize,
+                        "u" => {
+                            if let Some(code) = parameter {
+                                let code = if code < 0 { code + 65536 } else { code } as u32;
+                                pending_skip = 0;
+                                if let Some(c) = char::from_u32(code) {
+                                    emit(&mut text, &state, &mut pending_skip, c);
+                                }
+                                pending_skip = state.unicode_skip;
+                            }
+                        }
+                        _ => {
+                            if let (Some(c), false) = (control_word_text(&word), state.skip) {
+                                pending_skip = 0;
+                                text.push_str(c);
+                            }
+                        }
+                    },
+                }
+            }
+            byte => {
+                // Plain text is 7-bit in well-formed RTF; read any other byte as Windows-1252.
+                emit(&mut text, &state, &mut pending_skip, windows_1252(byte));
+                i += 1;
+            }
+        }
+    }
+
+    Ok(text)
+}
+
+/// A control word with its optional numeric parameter, or a control symbol.
+enum Control {
+    Word(String, Option<i32>),
+    Symbol(u8),
+}
+
+/// Reads the control word or symbol starting at `start` (just after the backslash), returning
+/// it and the position after it. The space delimiting a control word is consumed.
+fn read_control(bytes: &[u8], start: usize) -> (Control, usize) {
+    let mut i = start;
+    while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    if i == start {
+        let symbol = bytes.get(start).copied().unwrap_or(b'\\');
+        return (Control::Symbol(symbol), (start + 1).min(bytes.len()));
+    }
+    let word = String::from_utf8_lossy(&bytes[start..i]).into_owned();
+
+    let number_start = i;
+    if bytes.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    let parameter = std::str::from_utf8(&bytes[number_start..i])
+        .ok()
+        .and_then(|number| number.parse().ok());
+    if parameter.is_none() {
+        i = number_start;
+    }
+    if bytes.get(i) == Some(&b' ') {
+        i += 1;
+    }
+    (Control::Word(word, parameter), i)
+}
+
+/// Appends a character of document text, unless it is in a skipped destination or is the
+/// fallback of a preceding `\uN` escape.
+fn emit(text: &mut String, state: &GroupState, pending_skip: &mut usize, c: char) {
+    if *pending_skip > 0 {
+        *pending_skip -= 1;
+    } else if !state.skip {
+        text.push(c);
+    }
+}
+
+/// The text a control word stands for, if any.
+fn control_word_text(word: &str) -> Option<&'static str> {
+    Some(match word {
+        "par" | "line" | "sect" | "page" | "row" => "\n",
+        "tab" | "cell" | "nestcell" => "\t",
+        "emdash" => "\u{2014}",
+        "endash" => "\u{2013}",
+        "emspace" | "enspace" | "qmspace" => " ",
+        "bullet" => "\u{2022}",
+        "lquote" => "\u{2018}",
+        "rquote" => "\u{2019}",
+        "ldblquote" => "\u{201C}",
+        "rdblquote" => "\u{201D}",
+        _ => return None,
+    })
+}
+
+/// Decodes a byte in the Windows-1252 code page, the default for RTF.
+fn windows_1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '\u{20AC}', '\u{81}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{8D}',
+        '\u{017D}', '\u{8F}', '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}',
+        '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}',
+        '\u{9D}', '\u{017E}', '\u{0178}',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"{\rtf1\ansi\ansicpg1252\deff0
+{\fonttbl{\f0\fswiss Helvetica;}{\f1\froman Times New Roman;}}
+{\colortbl;\red255\green0\blue0;}
+{\stylesheet{\s0 Normal;}}
+{\info{\title Secret title}{\author Someone}}
+{\*\generator Riched20 10.0;}
+\pard\plain\f0\fs24 Hello {\b bold} world.\par
+Caf\'e9 na\u239?ve {\uc2\u8364 EU}\par
+{\*\shppict{\pict\pngblip 89504e470d0a}}
+{\field{\*\fldinst HYPERLINK "https://example.com"}{\fldrslt link text}}\par
+Tab\tab separated\line end\~with\-out
+}"#;
+
+    #[test]
+    fn test_rtf_text() {
+        let text = extract_rtf_text(DOCUMENT.as_bytes()).unwrap();
+        assert_eq!(
+            text.split_whitespace().collect::<Vec<_>>(),
+            vec![
+                "Hello",
+                "bold",
+                "world.",
+                "Café",
+                "naïve",
+                "€",
+                "link",
+                "text",
+                "Tab",
+                "separated",
+                "end",
+                "without"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escaped_braces_and_binary_data() {
+        let text = extract_rtf_text(br"{\rtf1 a \{b\} c\\d {\*\unknown skipped}{\pict\bin3 {x}}e")
+            .unwrap();
+        assert_eq!(text, r"a {b} c\d e");
+        assert!(extract_rtf_text(b"plain text").is_err());
+    }
+}
@@ -0,0 +1,653 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Cursor;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use zip::ZipArchive;
+
+use crate::extract::package::{attribute, read_entry, resolve};
+use crate::extract::Part;
+
+/// How many cells one ODS row element may expand to through its row and column repeat
+/// counts. Repeats of empty cells are free, but a repeated row with text would otherwise be
+/// copied up to a million times.
+const MAX_REPEATED_CELLS: usize = 10_000;
+
+/// Selects the cells of a spreadsheet that contribute to the word count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SheetOptions {
+    /// Names of the sheets to count, compared case-insensitively. Empty counts every sheet.
+    pub sheets: Vec<String>,
+    /// Columns to count, given by the text of their cell in the first row (`Description`),
+    /// compared case-insensitively, or by letter (`C`, `AB`) when no header matches. Empty
+    /// counts every column.
+    pub columns: Vec<String>,
+    /// Count numeric, date and boolean cells as well as text cells.
+    pub numbers: bool,
+}
+
+impl SheetOptions {
+    fn includes_sheet(&self, name: &str) -> bool {
+        self.sheets.is_empty()
+            || self
+                .sheets
+                .iter()
+                .any(|sheet| sheet.eq_ignore_ascii_case(name))
+    }
+
+    /// The columns selected by `columns`, given the sheet's first-row headers. Each selector
+    /// names the columns whose header it matches, or the column it spells as letters if none
+    /// does, so `ID` means the "ID" column rather than column 238 when such a header exists.
+    fn selected_columns(&self, headers: &HashMap<usize, String>) -> Option<Vec<usize>> {
+        if self.columns.is_empty() {
+            return None;
+        }
+        let mut selected = Vec::new();
+        for column in &self.columns {
+            let matching: Vec<usize> = headers
+                .iter()
+                .filter(|(_, header)| column.trim().eq_ignore_ascii_case(header.trim()))
+                .map(|(&index, _)| index)
+                .collect();
+            if matching.is_empty() {
+                selected.extend(column_index(column));
+            } else {
+                selected.extend(matching);
+            }
+        }
+        Some(selected)
+    }
+}
+
+/// One non-empty cell of a sheet.
+#[derive(Debug)]
+struct Cell {
+    /// Zero-based row and column.
+    row: usize,
+    column: usize,
+    text: String,
+    numeric: bool,
+}
+
+/// Turns the cells of one sheet into a part, keeping the selected ones.
+fn sheet_part(name: String, cells: Vec<Cell>, options: &SheetOptions) -> Part {
+    let first_row = cells.iter().map(|cell| cell.row).min().unwrap_or(0);
+    let headers: HashMap<usize, String> = cells
+        .iter()
+        .filter(|cell| cell.row == first_row && !cell.numeric)
+        .map(|cell| (cell.column, cell.text.clone()))
+        .collect();
+    let columns = options.selected_columns(&headers);
+
+    let text = cells
+        .into_iter()
+        .filter(|cell| options.numbers || !cell.numeric)
+        .filter(|cell| {
+            columns
+                .as_ref()
+                .is_none_or(|columns| columns.contains(&cell.column))
+        })
+        .map(|cell| cell.text)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Part {
+        included: options.includes_sheet(&name),
+        name,
+        text,
+    }
+}
+
+/// Converts column letters (`A`, `Z`, `AA`) to a zero-based index.
+fn column_index(letters: &str) -> Option<usize> {
+    let letters = letters.trim();
+    if letters.is_empty() || letters.len() > 3 || !letters.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return None;
+    }
+    letters
+        .chars()
+        .try_fold(0usize, |index, c| {
+            Some(index * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1))
+        })
+        .map(|index| index - 1)
+}
+
+/// Extracts an XLSX workbook as one part per worksheet, in workbook order. Text cells come
+/// from the shared strings table, inline strings and formula results; numeric cells are
+/// skipped unless `options.numbers` is set. Sheets not selected by `options.sheets` are
+/// reported but not counted.
+pub fn extract_xlsx_parts(
+    bytes: &[u8],
+    options: &SheetOptions,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let targets = relationship_targets(&read_entry(&mut archive, "xl/_rels/workbook.xml.rels")?)?;
+    let shared = if archive.by_name("xl/sharedStrings.xml").is_ok() {
+        shared_strings(&read_entry(&mut archive, "xl/sharedStrings.xml")?)?
+    } else {
+        Vec::new()
+    };
+
+    let mut parts = Vec::new();
+    for (name, id) in workbook_sheets(&read_entry(&mut archive, "xl/workbook.xml")?)? {
+        let Some(target) = targets.get(&id) else {
+            continue;
+        };
+        let entry = resolve("xl/workbook.xml", target);
+        let cells = xlsx_cells(&read_entry(&mut archive, &entry)?, &shared)?;
+        parts.push(sheet_part(name, cells, options));
+    }
+    Ok(parts)
+}
+
+/// The sheet names and relationship ids listed in `xl/workbook.xml`, in tab order.
+fn workbook_sheets(xml: &str) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut sheets = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                if let (Some(name), Some(id)) = (attribute(&e, b"name"), attribute(&e, b"id")) {
+                    sheets.push((name, id));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+/// Maps relationship ids to their targets.
+fn relationship_targets(xml: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut targets = HashMap::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                if let (Some(id), Some(target)) = (attribute(&e, b"Id"), attribute(&e, b"Target")) {
+                    targets.insert(id, target);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(targets)
+}
+
+/// Reads the shared strings table. Rich text runs are joined; phonetic guides are skipped.
+fn shared_strings(xml: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut phonetic_depth = 0usize;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = true,
+                b"rPh" => phonetic_depth += 1,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                b"rPh" => phonetic_depth = phonetic_depth.saturating_sub(1),
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::Text(e) if in_text && phonetic_depth == 0 => current.push_str(&e.unescape()?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// Reads the non-empty cells of an XLSX worksheet.
+fn xlsx_cells(xml: &str, shared: &[String]) -> Result<Vec<Cell>, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut cells = Vec::new();
+    let mut row = 0usize;
+    let mut next_row = 0usize;
+    let mut column = 0usize;
+    let mut next_column = 0usize;
+    // The type (`t` attribute) of the open cell, and its value or inline string so far.
+    let mut cell_type: Option<String> = None;
+    let mut value = String::new();
+    let mut in_value = false;
+    let mut phonetic_depth = 0usize;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"row" => {
+                    row = row_index(&e).unwrap_or(next_row);
+                    next_row = row + 1;
+                    next_column = 0;
+                }
+                b"c" => {
+                    column = cell_column(&e).unwrap_or(next_column);
+                    next_column = column + 1;
+                    cell_type = Some(attribute(&e, b"t").unwrap_or_else(|| "n".to_string()));
+                    value.clear();
+                }
+                b"v" | b"t" => in_value = cell_type.is_some(),
+                b"rPh" => phonetic_depth += 1,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"rPh" => phonetic_depth = phonetic_depth.saturating_sub(1),
+                b"c" => {
+                    let Some(kind) = cell_type.take() else {
+                        continue;
+                    };
+                    let (text, numeric) = match kind.as_str() {
+                        "s" => (
+                            value
+                                .trim()
+                                .parse::<usize>()
+                                .ok()
+                                .and_then(|index| shared.get(index))
+                                .cloned()
+                                .unwrap_or_default(),
+                            false,
+                        ),
+                        "str" | "inlineStr" => (std::mem::take(&mut value), false),
+                        _ => (std::mem::take(&mut value), true),
+                    };
+                    if !text.trim().is_empty() {
+                        cells.push(Cell {
+                            row,
+                            column,
+                            text,
+                            numeric,
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"c" => {
+                next_column = cell_column(&e).unwrap_or(next_column) + 1;
+            }
+            Event::Text(e) if in_value && phonetic_depth == 0 => value.push_str(&e.unescape()?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(cells)
+}
+
+/// The zero-based column of a `<c r="B7">`.
+fn cell_column(cell: &BytesStart) -> Option<usize> {
+    let reference = attribute(cell, b"r")?;
+    column_index(reference.trim_end_matches(|c: char| c.is_ascii_digit()))
+}
+
+/// The zero-based index of a `<row r="N">`.
+fn row_index(row: &BytesStart) -> Option<usize> {
+    attribute(row, b"r")?.parse::<usize>().ok()?.checked_sub(1)
+}
+
+/// Extracts an ODS spreadsheet as one part per table, from its `content.xml`. Cells whose
+/// `office:value-type` is not `string` are treated as numeric. Repeated rows and cells are
+/// counted as many times as they are shown, up to `MAX_REPEATED_CELLS` copies per row element;
+/// comments on cells are skipped.
+pub fn extract_ods_parts(
+    bytes: &[u8],
+    options: &SheetOptions,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let xml = read_entry(&mut archive, "content.xml")?;
+    let mut reader = Reader::from_str(&xml);
+
+    let mut parts = Vec::new();
+    let mut table: Option<(String, Vec<Cell>)> = None;
+    // The cells of the open row, with their repeat counts, and the row's own repeat count.
+    let mut row_cells: Vec<(Cell, usize)> = Vec::new();
+    let mut row = 0usize;
+    let mut row_repeat = 1usize;
+    let mut column = 0usize;
+    // The open cell: its repeat count, whether it is numeric, and its text so far.
+    let mut cell: Option<(usize, bool, String)> = None;
+    let mut skip_depth = 0usize;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"table" => {
+                    let name = attribute(&e, b"name").unwrap_or_default();
+                    table = Some((name, Vec::new()));
+                    row = 0;
+                }
+                b"table-row" => {
+                    row_repeat = repeat(&e, b"number-rows-repeated");
+                    row_cells.clear();
+                    column = 0;
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    let numeric = attribute(&e, b"value-type").is_some_and(|kind| kind != "string");
+                    cell = Some((
+                        repeat(&e, b"number-columns-repeated"),
+                        numeric,
+                        String::new(),
+                    ));
+                }
+                b"annotation" => skip_depth += 1,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"table" => {
+                    if let Some((name, cells)) = table.take() {
+                        parts.push(sheet_part(name, cells, options));
+                    }
+                }
+                b"table-row" => {
+                    if let Some((_, cells)) = table.as_mut() {
+                        let row_width: usize = row_cells.iter().map(|(_, repeat)| repeat).sum();
+                        let copies = row_repeat.min(MAX_REPEATED_CELLS / row_width.max(1)).max(1);
+                        for offset in 0..copies {
+                            if row_cells.is_empty() {
+                                break;
+                            }
+                            for (cell, repeat) in &row_cells {
+                                for copy in 0..*repeat {
+                                    cells.push(Cell {
+                                        row: row + offset,
+                                        column: cell.column + copy,
+                                        text: cell.text.clone(),
+                                        numeric: cell.numeric,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                    row += row_repeat;
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    if let Some((repeat, numeric, text)) = cell.take() {
+                        if !text.trim().is_empty() {
+                            row_cells.push((
+                                Cell {
+                                    row,
+                                    column,
+                                    text,
+                                    numeric,
+                                },
+                                repeat.min(MAX_REPEATED_CELLS),
+                            ));
+                        }
+                        column += repeat;
+                    }
+                }
+                b"annotation" => skip_depth = skip_depth.saturating_sub(1),
+                b"p" | b"h" => {
+                    if let (Some((_, _, text)), 0) = (cell.as_mut(), skip_depth) {
+                        text.push('\n');
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"table" => {
+                    let name = attribute(&e, b"name").unwrap_or_default();
+                    parts.push(sheet_part(name, Vec::new(), options));
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    column += repeat(&e, b"number-columns-repeated");
+                }
+                b"s" | b"tab" | b"line-break" => {
+                    if let (Some((_, _, text)), 0) = (cell.as_mut(), skip_depth) {
+                        text.push(' ');
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(e) => {
+                if let (Some((_, _, text)), 0) = (cell.as_mut(), skip_depth) {
+                    text.push_str(&e.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(parts)
+}
+
+/// The value of a `number-*-repeated` attribute, defaulting to 1.
+fn repeat(element: &BytesStart, name: &[u8]) -> usize {
+    attribute(element, name)
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(1)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_zip_file;
+    use tempfile::TempDir;
+
+    const WORKBOOK: &str = "<?xml version=\"1.0\"?>\
+        <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+        xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>\
+        <sheet name=\"Requirements\" sheetId=\"1\" r:id=\"rId2\"/>\
+        <sheet name=\"Notes\" sheetId=\"2\" r:id=\"rId1\"/></sheets></workbook>";
+    const RELS: &str = "<?xml version=\"1.0\"?>\
+        <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+        <Relationship Id=\"rId1\" Type=\"worksheet\" Target=\"worksheets/sheet2.xml\"/>\
+        <Relationship Id=\"rId2\" Type=\"worksheet\" Target=\"/xl/worksheets/sheet1.xml\"/>\
+        </Relationships>";
+    const SHARED: &str = "<?xml version=\"1.0\"?>\
+        <sst xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+        <si><t>ID</t></si><si><t>Description</t></si>\
+        <si><r><t>Must </t></r><r><t>log in</t></r><rPh><t>ignored</t></rPh></si>\
+        <si><t>Remember me</t></si></sst>";
+    const SHEET1: &str = "<?xml version=\"1.0\"?>\
+        <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>\
+        <row r=\"1\"><c r=\"A1\" t=\"s\"><v>0</v></c><c r=\"B1\" t=\"s\"><v>1</v></c></row>\
+        <row r=\"2\"><c r=\"A2\"><v>101</v></c><c r=\"B2\" t=\"s\"><v>2</v></c></row>\
+        <row r=\"3\"><c r=\"A3\"><v>102</v></c><c r=\"B3\" t=\"s\"><v>3</v></c>\
+        <c r=\"C3\" t=\"inlineStr\"><is><t>inline &amp; more</t></is></c>\
+        <c r=\"D3\" t=\"str\"><f>A3&amp;\"x\"</f><v>102x</v></c></row>\
+        </sheetData></worksheet>";
+    const SHEET2: &str = "<?xml version=\"1.0\"?>\
+        <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>\
+        <row><c t=\"inlineStr\"><is><t>Loose notes here</t></is></c></row></sheetData></worksheet>";
+
+    fn xlsx(dir: &TempDir) -> Vec<u8> {
+        let path = create_zip_file(
+            dir,
+            "matrix.xlsx",
+            &[
+                ("xl/workbook.xml", WORKBOOK),
+                ("xl/_rels/workbook.xml.rels", RELS),
+                ("xl/sharedStrings.xml", SHARED),
+                ("xl/worksheets/sheet1.xml", SHEET1),
+                ("xl/worksheets/sheet2.xml", SHEET2),
+            ],
+        );
+        std::fs::read(path).unwrap()
+    }
+
+    fn summary(parts: &[Part]) -> Vec<(&str, Vec<&str>, bool)> {
+        parts
+            .iter()
+            .map(|part| {
+                (
+                    part.name.as_str(),
+                    part.text.lines().collect(),
+                    part.included,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_xlsx_text_cells() {
+        let dir = TempDir::new().unwrap();
+        let parts = extract_xlsx_parts(&xlsx(&dir), &SheetOptions::default()).unwrap();
+        assert_eq!(
+            summary(&parts),
+            vec![
+                (
+                    "Requirements",
+                    vec![
+                        "ID",
+                        "Description",
+                        "Must log in",
+                        "Remember me",
+                        "inline & more",
+                        "102x"
+                    ],
+                    true
+                ),
+                ("Notes", vec!["Loose notes here"], true),
+            ]
+        );
+
+        let options = SheetOptions {
+            numbers: true,
+            ..SheetOptions::default()
+        };
+        let parts = extract_xlsx_parts(&xlsx(&dir), &options).unwrap();
+        assert!(parts[0].text.contains("101\nMust log in"));
+    }
+
+    #[test]
+    fn test_xlsx_sheet_and_column_selection() {
+        let dir = TempDir::new().unwrap();
+        let options = SheetOptions {
+            sheets: vec!["requirements".to_string()],
+            columns: vec!["description".to_string(), "c".to_string()],
+            numbers: false,
+        };
+        let parts = extract_xlsx_parts(&xlsx(&dir), &options).unwrap();
+        assert_eq!(
+            summary(&parts),
+            vec![
+                (
+                    "Requirements",
+                    vec!["Description", "Must log in", "Remember me", "inline & more"],
+                    true
+                ),
+                ("Notes", vec![], false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_headers_take_precedence_over_letters() {
+        let cell = |row, column, text: &str| Cell {
+            row,
+            column,
+            text: text.to_string(),
+            numeric: false,
+        };
+        let cells = || {
+            vec![
+                cell(0, 0, "ID"),
+                cell(0, 1, "Title"),
+                cell(0, 2, "A"),
+                cell(1, 0, "first"),
+                cell(1, 1, "second"),
+                cell(1, 2, "third"),
+            ]
+        };
+        let select = |columns: &[&str]| {
+            let options = SheetOptions {
+                columns: columns.iter().map(|c| c.to_string()).collect(),
+                ..SheetOptions::default()
+            };
+            sheet_part("Sheet1".to_string(), cells(), &options).text
+        };
+        assert_eq!(select(&["id"]), "ID\nfirst");
+        assert_eq!(select(&["a"]), "A\nthird");
+        assert_eq!(select(&["b"]), "Title\nsecond");
+    }
+
+    #[test]
+    fn test_xlsx_sparse_cells() {
+        // A self-closing cell with a reference moves the following unreferenced cells along.
+        let sheet = "<worksheet><sheetData><row r=\"1\">\
+            <c r=\"A1\" t=\"inlineStr\"><is><t>first</t></is></c><c r=\"E1\" s=\"1\"/>\
+            <c t=\"inlineStr\"><is><t>sixth</t></is></c></row></sheetData></worksheet>";
+        let cells = xlsx_cells(sheet, &[]).unwrap();
+        assert_eq!(
+            cells
+                .iter()
+                .map(|cell| (cell.column, cell.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "first"), (5, "sixth")]
+        );
+    }
+
+    #[test]
+    fn test_ods_cells() {
+        let content = "<?xml version=\"1.0\"?>\
+            <office:document-content xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+            xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+            xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\">\
+            <office:body><office:spreadsheet><table:table table:name=\"Sheet1\">\
+            <table:table-row><table:table-cell office:value-type=\"string\"><text:p>Name</text:p></table:table-cell>\
+            <table:table-cell office:value-type=\"string\"><text:p>Score</text:p></table:table-cell></table:table-row>\
+            <table:table-row table:number-rows-repeated=\"2\">\
+            <table:table-cell office:value-type=\"string\" table:number-columns-repeated=\"1\"><text:p>Same<text:s/>row</text:p>\
+            <office:annotation><text:p>a comment</text:p></office:annotation></table:table-cell>\
+            <table:table-cell office:value-type=\"float\" office:value=\"3\"><text:p>3</text:p></table:table-cell>\
+            <table:table-cell table:number-columns-repeated=\"1020\"/></table:table-row>\
+            <table:table-row table:number-rows-repeated=\"1048570\"><table:table-cell table:number-columns-repeated=\"1024\"/></table:table-row>\
+            </table:table><table:table table:name=\"Bomb\">\
+            <table:table-row table:number-rows-repeated=\"1048576\">\
+            <table:table-cell office:value-type=\"string\" table:number-columns-repeated=\"16384\"><text:p>x</text:p></table:table-cell>\
+            </table:table-row>\
+            </table:table><table:table table:name=\"Empty\"/></office:spreadsheet></office:body></office:document-content>";
+        let dir = TempDir::new().unwrap();
+        let path = create_zip_file(&dir, "sheet.ods", &[("content.xml", content)]);
+        let bytes = std::fs::read(path).unwrap();
+
+        let parts = extract_ods_parts(&bytes, &SheetOptions::default()).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[2].name, "Empty");
+        assert_eq!(parts[1].text.split_whitespace().count(), MAX_REPEATED_CELLS);
+        assert_eq!(
+            parts[0].text.split_whitespace().collect::<Vec<_>>(),
+            vec!["Name", "Score", "Same", "row", "Same", "row"]
+        );
+
+        let options = SheetOptions {
+            columns: vec!["Score".to_string()],
+            numbers: true,
+            ..SheetOptions::default()
+        };
+        let parts = extract_ods_parts(&bytes, &options).unwrap();
+        assert_eq!(
+            parts[0].text.split_whitespace().collect::<Vec<_>>(),
+            vec!["Score", "3", "3"]
+        );
+    }
+
+    #[test]
+    fn test_ods_other_prefixes() {
+        let content = "<?xml version=\"1.0\"?>\
+            <o:document-content xmlns:o=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+            xmlns:t=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+            xmlns:x=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\">\
+            <o:body><o:spreadsheet><t:table t:name=\"Sheet1\"><t:table-row>\
+            <t:table-cell o:value-type=\"string\"><x:p>one<x:s/>two</x:p></t:table-cell>\
+            </t:table-row></t:table></o:spreadsheet></o:body></o:document-content>";
+        let dir = TempDir::new().unwrap();
+        let path = create_zip_file(&dir, "sheet.ods", &[("content.xml", content)]);
+        let parts =
+            extract_ods_parts(&std::fs::read(path).unwrap(), &SheetOptions::default()).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].name, "Sheet1");
+        assert_eq!(parts[0].text.trim(), "one two");
+    }
+}
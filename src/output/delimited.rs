@@ -0,0 +1,367 @@
+//! CSV and TSV export with one row per file.
+//!
+//! Columns:
+//! `type,path,pattern,extractor,total_words,unique_words,cjk_characters,content_words,error,part`.
+//! `content_words` is the word count after stop-word filtering. `type` is `file` for counted files
+//! and `error` for files that failed; with summary rows enabled, each pattern adds a `pattern`
+//! row and the run ends with a `total` row. Paths are never truncated.
+//!
+//! Files with separately counted parts (such as DOCX footnotes) are followed by one row per part,
+//! with the part name in the `part` column and its `total_words`. The row type is `part` for
+//! parts included in the file's totals and `excluded_part` for the others.
+//!
+//! With `--top N` three more columns are added, `word,occurrences,percent`, and each file,
+//! pattern and the total is followed by `word` rows. The scope of a `word` row is given by its
+//! `path` and `pattern` columns: both set for a file, only `pattern` for a pattern, neither for
+//! the whole run.
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use crate::output::RenderOptions;
+use crate::{Format, Report, Summary, WordFrequency};
+
+const HEADER: [&str; 10] = [
+    "type",
+    "path",
+    "pattern",
+    "extractor",
+    "total_words",
+    "unique_words",
+    "cjk_characters",
+    "content_words",
+    "error",
+    "part",
+];
+
+const TOP_HEADER: [&str; 3] = ["word", "occurrences", "percent"];
+
+/// The field separator and its escaping rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Comma-separated, quoting fields as described in RFC 4180.
+    Comma,
+    /// Tab-separated; tabs and line breaks inside fields are replaced by spaces.
+    Tab,
+}
+
+impl Delimiter {
+    fn escape(self, field: &str) -> String {
+        match self {
+            Delimiter::Comma => {
+                if field.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.to_string()
+                }
+            }
+            Delimiter::Tab => field.replace(['\t', '\n', '\r'], " "),
+        }
+    }
+
+    fn separator(self) -> &'static str {
+        match self {
+            Delimiter::Comma => ",",
+            Delimiter::Tab => "\t",
+        }
+    }
+}
+
+/// Writes rows, padding each to the width of the header.
+struct RowWriter<'w, W: Write> {
+    writer: &'w mut W,
+    delimiter: Delimiter,
+    columns: usize,
+}
+
+impl<W: Write> RowWriter<'_, W> {
+    fn row(&mut self, fields: &[&str]) -> Result<(), Box<dyn Error>> {
+        let mut escaped: Vec<String> = fields
+            .iter()
+            .map(|field| self.delimiter.escape(field))
+            .collect();
+        escaped.resize(self.columns, String::new());
+        writeln!(self.writer, "{}", escaped.join(self.delimiter.separator()))?;
+        Ok(())
+    }
+
+    fn summary(
+        &mut self,
+        kind: &str,
+        pattern: &str,
+        summary: &Summary,
+        error: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.row(&[
+            kind,
+            "",
+            pattern,
+            "",
+            &summary.total_words.to_string(),
+            &summary.unique_words().to_string(),
+            &summary.cjk_characters.to_string(),
+            &summary.content_words.to_string(),
+            error,
+        ])
+    }
+
+    fn words(
+        &mut self,
+        path: &str,
+        pattern: &str,
+        frequencies: &[WordFrequency],
+    ) -> Result<(), Box<dyn Error>> {
+        for frequency in frequencies {
+            self.row(&[
+                "word",
+                path,
+                pattern,
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                &frequency.word,
+                &frequency.occurrences.to_string(),
+                &format!("{:.2}", frequency.percent),
+            ])?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the report as delimited rows, optionally followed by per-pattern and grand total rows
+/// and most-frequent-word rows.
+pub fn write_report(
+    report: &Report,
+    render: &RenderOptions,
+    writer: &mut impl Write,
+    delimiter: Delimiter,
+) -> Result<(), Box<dyn Error>> {
+    let mut header = HEADER.to_vec();
+    if render.top > 0 {
+        header.extend(TOP_HEADER);
+    }
+    let mut rows = RowWriter {
+        writer,
+        delimiter,
+        columns: header.len(),
+    };
+    rows.row(&header)?;
+
+    for pattern in &report.patterns {
+        for count in &pattern.files {
+            rows.row(&[
+                "file",
+                &count.file_path,
+                &pattern.pattern,
+                count.format.name(),
+                &count.total_words.to_string(),
+                &count.unique_words.to_string(),
+                &count.cjk_characters.to_string(),
+                &count.content_words.to_string(),
+                "",
+            ])?;
+            for part in &count.parts {
+                rows.row(&[
+                    if part.included {
+                        "part"
+                    } else {
+                        "excluded_part"
+                    },
+                    &count.file_path,
+                    &pattern.pattern,
+                    count.format.name(),
+                    &part.total_words.to_string(),
+                    "",
+                    "",
+                    "",
+                    "",
+                    &part.name,
+                ])?;
+            }
+            if render.top > 0 {
+                rows.words(
+                    &count.file_path,
+                    &pattern.pattern,
+                    &count.top_words(render.top),
+                )?;
+            }
+        }
+        for error in &pattern.errors {
+            let format = Format::from_path(Path::new(&error.file_path));
+            rows.row(&[
+                "error",
+                &error.file_path,
+                &pattern.pattern,
+                format.name(),
+                "",
+                "",
+                "",
+                "",
+                &error.message,
+            ])?;
+        }
+        if render.summary_rows {
+            let error = pattern.error.as_deref().unwrap_or("");
+            rows.summary("pattern", &pattern.pattern, &pattern.summary, error)?;
+        }
+        if render.top > 0 && pattern.error.is_none() {
+            rows.words("", &pattern.pattern, &pattern.summary.top_words(render.top))?;
+        }
+    }
+
+    if render.summary_rows {
+        rows.summary("total", "", &report.total, "")?;
+    }
+    if render.top > 0 {
+        rows.words("", "", &report.total.top_words(render.top))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_docx_with_parts, create_test_file};
+    use crate::Options;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(Delimiter::Comma.escape("plain"), "plain");
+        assert_eq!(Delimiter::Comma.escape("a,b"), "\"a,b\"");
+        assert_eq!(Delimiter::Comma.escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(Delimiter::Tab.escape("a\tb\nc"), "a b c");
+    }
+
+    #[test]
+    fn test_csv_rows_keep_full_paths() {
+        let dir = TempDir::new().unwrap();
+        let long_name = format!("{}.txt", "a_very_long_file_name".repeat(4));
+        let path = create_test_file(&dir, &long_name, "one two two");
+        create_test_file(&dir, "bad.pdf", "not a pdf");
+
+        let patterns = vec![format!("{}/*.*", dir.path().to_str().unwrap())];
+        let report = Report::build(&patterns, &Options::default()).unwrap();
+
+        let render = RenderOptions {
+            summary_rows: true,
+            ..RenderOptions::default()
+        };
+        let mut buffer = Vec::new();
+        write_report(&report, &render, &mut buffer, Delimiter::Comma).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "type,path,pattern,extractor,total_words,unique_words,cjk_characters,content_words,error,part"
+        );
+        assert_eq!(
+            lines[1],
+            format!("file,{},{},txt,3,2,0,3,,", path, patterns[0])
+        );
+        assert!(lines[2].starts_with("error,") && lines[2].contains(",pdf,,,,,"));
+        assert_eq!(lines[3], format!("pattern,,{},,3,2,0,3,,", patterns[0]));
+        assert_eq!(lines[4], "total,,,,3,2,0,3,,");
+    }
+
+    #[test]
+    fn test_tsv_without_summary_rows() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.md", "Hello *world*");
+
+        let patterns = vec![format!("{}/*.md", dir.path().to_str().unwrap())];
+        let report = Report::build(&patterns, &Options::default()).unwrap();
+
+        let mut buffer = Vec::new();
+        write_report(
+            &report,
+            &RenderOptions::default(),
+            &mut buffer,
+            Delimiter::Tab,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        assert_eq!(output.lines().count(), 2);
+        assert!(output
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with("\tmd\t2\t2\t0\t2\t\t"));
+    }
+
+    #[test]
+    fn test_docx_part_rows() {
+        let dir = TempDir::new().unwrap();
+        let path = create_docx_with_parts(
+            &dir,
+            "a.docx",
+            &[
+                ("word/document.xml", "main text"),
+                ("word/comments.xml", "reviewer remark here"),
+            ],
+        );
+
+        let patterns = vec![format!("{}/*.docx", dir.path().to_str().unwrap())];
+        let report = Report::build(&patterns, &Options::default()).unwrap();
+        let mut buffer = Vec::new();
+        write_report(
+            &report,
+            &RenderOptions::default(),
+            &mut buffer,
+            Delimiter::Comma,
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[2],
+            format!("part,{},{},docx,2,,,,,body", path, patterns[0])
+        );
+        assert_eq!(
+            lines[3],
+            format!("excluded_part,{},{},docx,3,,,,,comments", path, patterns[0])
+        );
+    }
+
+    #[test]
+    fn test_top_word_rows() {
+        let dir = TempDir::new().unwrap();
+        let path = create_test_file(&dir, "a.txt", "to be or not to be");
+
+        let patterns = vec![format!("{}/*.txt", dir.path().to_str().unwrap())];
+        let report = Report::build(&patterns, &Options::default()).unwrap();
+
+        let render = RenderOptions {
+            top: 2,
+            ..RenderOptions::default()
+        };
+        let mut buffer = Vec::new();
+        write_report(&report, &render, &mut buffer, Delimiter::Comma).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].ends_with(",error,part,word,occurrences,percent"));
+        assert!(lines[1].ends_with(",6,4,0,6,,,,,"));
+        assert_eq!(
+            lines[2],
+            format!("word,{},{},,,,,,,,be,2,33.33", path, patterns[0])
+        );
+        assert_eq!(
+            lines[3],
+            format!("word,{},{},,,,,,,,to,2,33.33", path, patterns[0])
+        );
+        assert_eq!(lines[4], format!("word,,{},,,,,,,,be,2,33.33", patterns[0]));
+        assert_eq!(lines[6], "word,,,,,,,,,,be,2,33.33");
+        assert_eq!(lines.len(), 8);
+    }
+}
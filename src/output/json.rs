@@ -0,0 +1,281 @@
+//! Machine-readable JSON output.
+//!
+//! The document has this shape (schema version 1):
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "generator": "mdwc 1.0.0",
+//!   "patterns": [
+//!     {
+//!       "pattern": "docs/*.md",
+//!       "files": [{ "file_path": "docs/a.md", "format": "md", "unique_words": 10, "total_words": 25, "content_words": 25, "cjk_characters": 0 }],
+//!       "errors": [{ "file_path": "docs/b.md", "message": "..." }],
+//!       "error": null,
+//!       "summary": { "files": 1, "unique_words": 10, "total_words": 25, "content_words": 25, "cjk_characters": 0, "unique_ratio": 40.0 }
+//!     }
+//!   ],
+//!   "total": { "files": 1, "unique_words": 10, "total_words": 25, "content_words": 25, "cjk_characters": 0, "unique_ratio": 40.0 }
+//! }
+//! ```
+//!
+//! `content_words` is the word count after stop-word filtering (equal to `total_words` when no
+//! stop words are configured); `unique_words`, `unique_ratio` and `top_words` are based on it.
+//!
+//! Files with separately counted parts (such as DOCX footnotes) carry a `parts` list of
+//! `{ "name", "total_words", "included" }` entries; only included parts count towards the
+//! file's totals.
+//!
+//! With `--top N`, every file, pattern summary and the total also carry a `top_words` list of
+//! `{ "word", "occurrences", "percent" }` entries.
+//!
+//! Fields may be added within a schema version; removing or changing the meaning of a field
+//! bumps [`SCHEMA_VERSION`].
+
+use std::error::Error;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::output::RenderOptions;
+use crate::{FileError, PartCount, PatternReport, Report, Summary, WordCount, WordFrequency};
+
+/// Version of the JSON document layout.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema_version: u32,
+    generator: String,
+    patterns: Vec<JsonPattern<'a>>,
+    total: JsonSummary<'a>,
+}
+
+#[derive(Serialize)]
+struct JsonPattern<'a> {
+    pattern: &'a str,
+    files: Vec<JsonFile<'a>>,
+    errors: Vec<JsonError<'a>>,
+    error: Option<&'a str>,
+    summary: JsonSummary<'a>,
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    file_path: &'a str,
+    format: &'static str,
+    unique_words: usize,
+    total_words: usize,
+    content_words: usize,
+    cjk_characters: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parts: Vec<JsonPart<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_words: Option<Vec<JsonFrequency<'a>>>,
+}
+
+#[derive(Serialize)]
+struct JsonPart<'a> {
+    name: &'a str,
+    total_words: usize,
+    included: bool,
+}
+
+impl<'a> From<&'a PartCount> for JsonPart<'a> {
+    fn from(part: &'a PartCount) -> Self {
+        JsonPart {
+            name: &part.name,
+            total_words: part.total_words,
+            included: part.included,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonError<'a> {
+    file_path: &'a str,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonSummary<'a> {
+    files: usize,
+    unique_words: usize,
+    total_words: usize,
+    content_words: usize,
+    cjk_characters: usize,
+    unique_ratio: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_words: Option<Vec<JsonFrequency<'a>>>,
+}
+
+#[derive(Serialize)]
+struct JsonFrequency<'a> {
+    word: &'a str,
+    occurrences: usize,
+    percent: f64,
+}
+
+impl<'a> From<&'a WordFrequency> for JsonFrequency<'a> {
+    fn from(frequency: &'a WordFrequency) -> Self {
+        JsonFrequency {
+            word: &frequency.word,
+            occurrences: frequency.occurrences,
+            percent: frequency.percent,
+        }
+    }
+}
+
+/// Converts a frequency listing, omitting it entirely when `--top` was not requested.
+fn top_list(frequencies: &[WordFrequency], top: usize) -> Option<Vec<JsonFrequency<'_>>> {
+    (top > 0).then(|| frequencies.iter().map(JsonFrequency::from).collect())
+}
+
+impl<'a> From<&'a FileError> for JsonError<'a> {
+    fn from(error: &'a FileError) -> Self {
+        JsonError {
+            file_path: &error.file_path,
+            message: &error.message,
+        }
+    }
+}
+
+/// Owns the frequency listings so the borrowed JSON view can refer to them.
+struct TopWords {
+    files: Vec<Vec<WordFrequency>>,
+    summary: Vec<WordFrequency>,
+}
+
+impl TopWords {
+    fn for_pattern(pattern: &PatternReport, top: usize) -> TopWords {
+        TopWords {
+            files: pattern
+                .files
+                .iter()
+                .map(|count| count.top_words(top))
+                .collect(),
+            summary: pattern.summary.top_words(top),
+        }
+    }
+}
+
+fn file_view<'a>(count: &'a WordCount, top: &'a [WordFrequency], n: usize) -> JsonFile<'a> {
+    JsonFile {
+        file_path: &count.file_path,
+        format: count.format.name(),
+        unique_words: count.unique_words,
+        total_words: count.total_words,
+        content_words: count.content_words,
+        cjk_characters: count.cjk_characters,
+        parts: count.parts.iter().map(JsonPart::from).collect(),
+        top_words: top_list(top, n),
+    }
+}
+
+fn summary_view<'a>(summary: &Summary, top: &'a [WordFrequency], n: usize) -> JsonSummary<'a> {
+    JsonSummary {
+        files: summary.files,
+        unique_words: summary.unique_words(),
+        total_words: summary.total_words,
+        content_words: summary.content_words,
+        cjk_characters: summary.cjk_characters,
+        unique_ratio: summary.unique_ratio(),
+        top_words: top_list(top, n),
+    }
+}
+
+fn pattern_view<'a>(pattern: &'a PatternReport, top: &'a TopWords, n: usize) -> JsonPattern<'a> {
+    JsonPattern {
+        pattern: &pattern.pattern,
+        files: pattern
+            .files
+            .iter()
+            .zip(&top.files)
+            .map(|(count, words)| file_view(count, words, n))
+            .collect(),
+        errors: pattern.errors.iter().map(JsonError::from).collect(),
+        error: pattern.error.as_deref(),
+        summary: summary_view(&pattern.summary, &top.summary, n),
+    }
+}
+
+/// Writes the report as a single pretty-printed JSON document.
+pub fn write_report(
+    report: &Report,
+    render: &RenderOptions,
+    writer: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let n = render.top;
+    let pattern_top: Vec<TopWords> = report
+        .patterns
+        .iter()
+        .map(|pattern| TopWords::for_pattern(pattern, n))
+        .collect();
+    let total_top = report.total.top_words(n);
+
+    let document = JsonReport {
+        schema_version: SCHEMA_VERSION,
+        generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        patterns: report
+            .patterns
+            .iter()
+            .zip(&pattern_top)
+            .map(|(pattern, top)| pattern_view(pattern, top, n))
+            .collect(),
+        total: summary_view(&report.total, &total_top, n),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &document)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_file;
+    use crate::Options;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_json_report() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "good.txt", "hello hello world");
+        create_test_file(&dir, "bad.pdf", "not a real pdf");
+
+        let patterns = vec![format!("{}/*.*", dir.path().to_str().unwrap())];
+        let report = Report::build(&patterns, &Options::default()).unwrap();
+        let mut buffer = Vec::new();
+        write_report(&report, &RenderOptions::default(), &mut buffer).unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        let pattern = &value["patterns"][0];
+        assert_eq!(pattern["files"][0]["format"], "txt");
+        assert_eq!(pattern["files"][0]["total_words"], 3);
+        assert_eq!(pattern["files"][0]["unique_words"], 2);
+        assert!(pattern["errors"][0]["file_path"]
+            .as_str()
+            .unwrap()
+            .ends_with("bad.pdf"));
+        assert!(pattern["error"].is_null());
+        assert_eq!(value["total"]["files"], 1);
+        assert_eq!(value["total"]["total_words"], 3);
+        assert_eq!(value["total"]["content_words"], 3);
+        assert!(value["total"].get("top_words").is_none());
+
+        assert!(pattern["files"][0].get("parts").is_none());
+
+        let render = RenderOptions {
+            top: 1,
+            ..RenderOptions::default()
+        };
+        let mut buffer = Vec::new();
+        write_report(&report, &render, &mut buffer).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        let top = &value["patterns"][0]["files"][0]["top_words"];
+        assert_eq!(top.as_array().unwrap().len(), 1);
+        assert_eq!(top[0]["word"], "hello");
+        assert_eq!(top[0]["occurrences"], 2);
+        assert_eq!(value["total"]["top_words"][0]["word"], "hello");
+    }
+}
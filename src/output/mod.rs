@@ -0,0 +1,77 @@
+//! Rendering a [`Report`](crate::Report) in the supported output formats.
+
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::Report;
+
+pub mod delimited;
+pub mod json;
+pub mod text;
+
+use delimited::Delimiter;
+
+/// The output formats selectable with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Colored, human-readable tables.
+    #[default]
+    Text,
+    /// A single JSON document (see [`json::SCHEMA_VERSION`]).
+    Json,
+    /// Comma-separated rows, one per file.
+    Csv,
+    /// Tab-separated rows, one per file.
+    Tsv,
+}
+
+/// Settings that affect how a report is rendered rather than how it is counted.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Append per-pattern and grand total rows to CSV/TSV output.
+    pub summary_rows: bool,
+    /// List the N most frequent words per file, per pattern and overall (0 disables).
+    pub top: usize,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        })
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(format!("Unknown output format '{}'", s)),
+        }
+    }
+}
+
+/// Writes the report in the requested format.
+pub fn write_report(
+    report: &Report,
+    format: OutputFormat,
+    render: &RenderOptions,
+    writer: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        OutputFormat::Text => text::write_report(report, render, writer),
+        OutputFormat::Json => json::write_report(report, render, writer),
+        OutputFormat::Csv => delimited::write_report(report, render, writer, Delimiter::Comma),
+        OutputFormat::Tsv => delimited::write_report(report, render, writer, Delimiter::Tab),
+    }
+}
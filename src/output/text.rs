@@ -0,0 +1,274 @@
+//! The default colored, human-readable report.
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use colored::*;
+
+use crate::output::RenderOptions;
+use crate::{PartCount, PatternReport, Report, WordFrequency};
+
+const FILENAME_WIDTH: usize = 45; // Maximum width for the file name column
+
+/// Formats a number with commas.
+pub fn format_number(num: usize) -> String {
+    num.to_string()
+        .chars()
+        .rev()
+        .collect::<Vec<_>>()
+        .chunks(3)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(",")
+        .chars()
+        .rev()
+        .collect()
+}
+
+/// Truncates a file name if it exceeds `max_len` characters and appends an ellipsis.
+pub fn format_filename(name: &str, max_len: usize) -> String {
+    if name.chars().count() > max_len {
+        // Reserve space for the ellipsis ("...")
+        let truncated: String = name.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", truncated)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Writes the report as a table per pattern followed by the grand total. Files that could not
+/// be counted are reported on stderr.
+pub fn write_report(
+    report: &Report,
+    render: &RenderOptions,
+    writer: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    for pattern in &report.patterns {
+        for error in &pattern.errors {
+            eprintln!("Error processing {}: {}", error.file_path, error.message);
+        }
+        match &pattern.error {
+            None => write_pattern(pattern, render, writer)?,
+            Some(e) => writeln!(
+                writer,
+                "{} processing pattern '{}': {}",
+                "Error".red().bold(),
+                pattern.pattern.yellow(),
+                e
+            )?,
+        }
+    }
+
+    // Print grand total if we processed at least one file.
+    let total = &report.total;
+    if total.files > 0 {
+        writeln!(writer, "{}", "=".repeat(80).blue())?;
+        writeln!(
+            writer,
+            "{} ({} files processed):",
+            "GRAND TOTAL".blue().bold(),
+            format_number(total.files).bright_yellow()
+        )?;
+        writeln!(
+            writer,
+            "{} {:>10}\n{} {:>10}\n{} {}",
+            "Total unique words:".dimmed(),
+            format_number(total.unique_words()).bright_cyan(),
+            "Total words:       ".dimmed(),
+            format_number(total.total_words).bright_cyan(),
+            "Unique ratio:      ".dimmed(),
+            format!("{:>9.1}%", total.unique_ratio()).green()
+        )?;
+        if total.content_words != total.total_words {
+            writeln!(
+                writer,
+                "{} {:>10}",
+                "Without stop words:".dimmed(),
+                format_number(total.content_words).bright_cyan()
+            )?;
+        }
+        if total.cjk_characters > 0 {
+            writeln!(
+                writer,
+                "{} {:>10}",
+                "CJK characters:    ".dimmed(),
+                format_number(total.cjk_characters).bright_cyan()
+            )?;
+        }
+        if render.top > 0 {
+            writeln!(writer, "{}", "Most frequent words:".dimmed())?;
+            write_top_words(writer, &total.top_words(render.top))?;
+        }
+        writeln!(writer, "{}", "=".repeat(80).blue())?;
+    }
+
+    Ok(())
+}
+
+fn write_pattern(
+    pattern: &PatternReport,
+    render: &RenderOptions,
+    writer: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        writer,
+        "\n{} '{}':",
+        "Analysis for files matching pattern".blue().bold(),
+        pattern.pattern.yellow()
+    )?;
+    writeln!(writer, "{}", "-".repeat(80).dimmed())?;
+
+    for result in &pattern.files {
+        // Extract just the file name from the full path.
+        let raw_name = Path::new(&result.file_path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or(&result.file_path);
+        let display_name = format_filename(raw_name, FILENAME_WIDTH);
+
+        // Print file results using fixed-width formatting.
+        write!(
+            writer,
+            "{:<width$}: {:>10} {} {:>10} {}",
+            display_name,
+            format_number(result.unique_words).cyan(),
+            "unique words out of".dimmed(),
+            format_number(result.total_words).cyan(),
+            "total words".dimmed(),
+            width = FILENAME_WIDTH
+        )?;
+        write_content_suffix(writer, result.total_words, result.content_words)?;
+        write_cjk_suffix(writer, result.cjk_characters)?;
+        writeln!(writer)?;
+        write_parts(writer, &result.parts)?;
+        if render.top > 0 {
+            write_top_words(writer, &result.top_words(render.top))?;
+        }
+    }
+
+    // Print pattern summary.
+    writeln!(writer, "{}", "-".repeat(80).dimmed())?;
+    write!(
+        writer,
+        "{} {:>10} {} {:>10} {}",
+        "Summary for pattern:".blue().bold(),
+        format_number(pattern.summary.unique_words()).bright_cyan(),
+        "unique words out of".dimmed(),
+        format_number(pattern.summary.total_words).bright_cyan(),
+        "total words".dimmed()
+    )?;
+    write_content_suffix(
+        writer,
+        pattern.summary.total_words,
+        pattern.summary.content_words,
+    )?;
+    write_cjk_suffix(writer, pattern.summary.cjk_characters)?;
+    writeln!(writer)?;
+    if render.top > 0 {
+        write_top_words(writer, &pattern.summary.top_words(render.top))?;
+    }
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+/// Writes an indented line per document part, marking parts left out of the totals.
+fn write_parts(writer: &mut impl Write, parts: &[PartCount]) -> Result<(), Box<dyn Error>> {
+    // Chapter titles can be long; keep the counts in one column.
+    let width = parts
+        .iter()
+        .map(|part| part.name.chars().count() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    for part in parts {
+        write!(
+            writer,
+            "    {:<width$} {:>10} {}",
+            format!("{}:", part.name),
+            format_number(part.total_words).cyan(),
+            "words".dimmed()
+        )?;
+        if !part.included {
+            write!(writer, " {}", "(not counted)".dimmed())?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Writes a ranked, indented list of words with their counts and share of the total.
+fn write_top_words(
+    writer: &mut impl Write,
+    frequencies: &[WordFrequency],
+) -> Result<(), Box<dyn Error>> {
+    for (rank, frequency) in frequencies.iter().enumerate() {
+        writeln!(
+            writer,
+            "    {:>3}. {:<30} {:>10} {}",
+            rank + 1,
+            frequency.word,
+            format_number(frequency.occurrences).cyan(),
+            format!("{:>6.2}%", frequency.percent).green()
+        )?;
+    }
+    Ok(())
+}
+
+/// Appends the word count after stop-word filtering to a line, if any words were filtered.
+fn write_content_suffix(
+    writer: &mut impl Write,
+    total_words: usize,
+    content_words: usize,
+) -> Result<(), Box<dyn Error>> {
+    if content_words != total_words {
+        write!(
+            writer,
+            " {}{} {}{}",
+            "(".dimmed(),
+            format_number(content_words).cyan(),
+            "without stop words".dimmed(),
+            ")".dimmed()
+        )?;
+    }
+    Ok(())
+}
+
+/// Appends the CJK character count to a line, if any were counted.
+fn write_cjk_suffix(writer: &mut impl Write, cjk_characters: usize) -> Result<(), Box<dyn Error>> {
+    if cjk_characters > 0 {
+        write!(
+            writer,
+            " {} {} {}",
+            "+".dimmed(),
+            format_number(cjk_characters).cyan(),
+            "CJK characters".dimmed()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(10), "10");
+        assert_eq!(format_number(100), "100");
+        assert_eq!(format_number(1000), "1,000");
+        assert_eq!(format_number(1000000), "1,000,000");
+        assert_eq!(format_number(123456789), "123,456,789");
+    }
+
+    #[test]
+    fn test_format_filename() {
+        assert_eq!(format_filename("short.txt", 10), "short.txt");
+        assert_eq!(format_filename("exactsize.txt", 13), "exactsize.txt");
+        assert_eq!(format_filename("longerfilename.txt", 10), "longerf...");
+        // Check edge case where max_len is very small
+        assert_eq!(format_filename("abcd", 3), "...");
+    }
+}
@@ -0,0 +1,108 @@
+//! Results for a whole run: every pattern's files, errors and totals.
+
+use std::error::Error;
+
+use rayon::ThreadPoolBuilder;
+
+use crate::{process_input, FileError, Input, Options, Summary, WordCount};
+
+/// The outcome of one pattern or other input.
+#[derive(Debug)]
+pub struct PatternReport {
+    /// The pattern as given, or the [`Input::label`] of a file list.
+    pub pattern: String,
+    pub files: Vec<WordCount>,
+    /// Files that matched but could not be counted.
+    pub errors: Vec<FileError>,
+    /// Set when the pattern as a whole failed (invalid pattern or no countable files).
+    pub error: Option<String>,
+    pub summary: Summary,
+}
+
+/// Per-pattern results plus the grand total across all of them.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub patterns: Vec<PatternReport>,
+    pub total: Summary,
+}
+
+impl Report {
+    /// Processes each pattern in turn and aggregates the results. Files are counted on a
+    /// thread pool with `options.jobs` threads (one per core when 0).
+    pub fn build(patterns: &[String], options: &Options) -> Result<Report, Box<dyn Error>> {
+        let inputs: Vec<Input> = patterns.iter().cloned().map(Input::Pattern).collect();
+        Report::build_inputs(&inputs, options)
+    }
+
+    /// Like [`Report::build`], for a mix of patterns and file lists.
+    pub fn build_inputs(inputs: &[Input], options: &Options) -> Result<Report, Box<dyn Error>> {
+        if options.jobs == 1 {
+            return Ok(Report::process_inputs(inputs, options));
+        }
+        let pool = ThreadPoolBuilder::new().num_threads(options.jobs).build()?;
+        Ok(pool.install(|| Report::process_inputs(inputs, options)))
+    }
+
+    /// Processes each input in turn, counting its files on the current rayon pool.
+    fn process_inputs(inputs: &[Input], options: &Options) -> Report {
+        let mut report = Report::default();
+
+        for input in inputs {
+            let mut pattern_report = PatternReport {
+                pattern: input.label(),
+                files: Vec::new(),
+                errors: Vec::new(),
+                error: None,
+                summary: Summary::new(),
+            };
+
+            match process_input(input, options) {
+                Ok(result) => {
+                    for count in &result.files {
+                        pattern_report.summary.add(count);
+                    }
+                    if result.files.is_empty() {
+                        pattern_report.error = Some("No files found matching the pattern".into());
+                    }
+                    pattern_report.files = result.files;
+                    pattern_report.errors = result.errors;
+                }
+                Err(e) => pattern_report.error = Some(e.to_string()),
+            }
+
+            report.total.merge(&pattern_report.summary);
+            report.patterns.push(pattern_report);
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_file;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_report() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.txt", "hello world");
+        create_test_file(&dir, "b.md", "hello *rust*");
+
+        let root = dir.path().to_str().unwrap();
+        let patterns = vec![
+            format!("{}/*.txt", root),
+            format!("{}/*.md", root),
+            format!("{}/*.pdf", root),
+        ];
+        let report = Report::build(&patterns, &Options::default()).unwrap();
+
+        assert_eq!(report.patterns.len(), 3);
+        assert_eq!(report.patterns[0].summary.total_words, 2);
+        assert!(report.patterns[2].error.is_some());
+        assert_eq!(report.total.files, 2);
+        assert_eq!(report.total.total_words, 4);
+        assert_eq!(report.total.unique_words(), 3);
+    }
+}
@@ -0,0 +1,124 @@
+//! Stop-word lists used to drop very common words from unique and frequency statistics.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::str::FromStr;
+
+/// A language with a bundled stop-word list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+impl Language {
+    /// The bundled list, one word per line with `#` comments.
+    fn list(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("stopwords/en.txt"),
+            Language::German => include_str!("stopwords/de.txt"),
+            Language::French => include_str!("stopwords/fr.txt"),
+            Language::Spanish => include_str!("stopwords/es.txt"),
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            "de" | "german" => Ok(Language::German),
+            "fr" | "french" => Ok(Language::French),
+            "es" | "spanish" => Ok(Language::Spanish),
+            _ => Err(format!("No stop-word list for language '{}'", s)),
+        }
+    }
+}
+
+/// A set of lowercase words excluded from the vocabulary. An empty set disables filtering.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StopWords {
+    words: HashSet<String>,
+}
+
+impl StopWords {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the bundled list for `language`.
+    pub fn add_language(&mut self, language: Language) {
+        self.add_list(language.list());
+    }
+
+    /// Adds the words of a list file: whitespace-separated words, with `#` starting a comment.
+    pub fn add_file(&mut self, path: &str) -> Result<(), Box<dyn Error>> {
+        let list = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read stop words from '{}': {}", path, e))?;
+        self.add_list(&list);
+        Ok(())
+    }
+
+    /// Adds the words of a list in the same format as [`StopWords::add_file`].
+    pub fn add_list(&mut self, list: &str) {
+        for line in list.lines() {
+            let line = line.split('#').next().unwrap_or("");
+            self.words
+                .extend(line.split_whitespace().map(|word| word.to_lowercase()));
+        }
+    }
+
+    /// Returns true if `word` (already lowercased by the tokenizer) is a stop word.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// Returns true when no stop words are configured.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Number of distinct stop words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_lists() {
+        for language in ["en", "de", "fr", "es"] {
+            let mut stop_words = StopWords::new();
+            stop_words.add_language(language.parse().unwrap());
+            assert!(stop_words.len() > 100, "{} list is too short", language);
+        }
+
+        let mut stop_words = StopWords::new();
+        stop_words.add_language(Language::English);
+        assert!(stop_words.contains("the"));
+        assert!(stop_words.contains("don't"));
+        assert!(!stop_words.contains("#"));
+        assert!(!stop_words.contains("rust"));
+        assert!("klingon".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_custom_list() {
+        let mut stop_words = StopWords::new();
+        assert!(stop_words.is_empty());
+        stop_words.add_list("# project jargon\nFoo bar  # trailing comment\n\n  baz\n");
+        assert_eq!(stop_words.len(), 3);
+        assert!(stop_words.contains("foo"));
+        assert!(stop_words.contains("baz"));
+        assert!(stop_words.add_file("/nonexistent/stopwords.txt").is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Aggregation of per-file counts into pattern and grand totals.
+
+use std::collections::HashMap;
+
+use crate::WordCount;
+
+/// A word and how often it occurs within some scope (a file, pattern or the whole run).
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordFrequency {
+    pub word: String,
+    pub occurrences: usize,
+    /// Occurrences as a percentage of the scope's words after stop-word filtering.
+    pub percent: f64,
+}
+
+/// Returns the `n` most frequent words, most frequent first. Ties are broken alphabetically so
+/// the listing is stable between runs.
+pub fn top_words(
+    vocabulary: &HashMap<String, usize>,
+    total_words: usize,
+    n: usize,
+) -> Vec<WordFrequency> {
+    let mut entries: Vec<(&String, &usize)> = vocabulary.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    entries
+        .into_iter()
+        .take(n)
+        .map(|(word, &occurrences)| WordFrequency {
+            word: word.clone(),
+            occurrences,
+            percent: if total_words == 0 {
+                0.0
+            } else {
+                occurrences as f64 / total_words as f64 * 100.0
+            },
+        })
+        .collect()
+}
+
+/// Running totals over a group of files, such as all matches of one pattern or the whole run.
+#[derive(Debug, Default, Clone)]
+pub struct Summary {
+    /// Number of files added.
+    pub files: usize,
+    /// Sum of the files' total word counts.
+    pub total_words: usize,
+    /// Sum of the files' word counts after stop-word filtering.
+    pub content_words: usize,
+    /// Sum of the files' CJK character counts.
+    pub cjk_characters: usize,
+    vocabulary: HashMap<String, usize>,
+}
+
+impl Summary {
+    /// Creates an empty summary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds one file's count, merging its vocabulary so the unique word count stays exact
+    /// across files.
+    pub fn add(&mut self, count: &WordCount) {
+        self.files += 1;
+        self.total_words += count.total_words;
+        self.content_words += count.content_words;
+        self.cjk_characters += count.cjk_characters;
+        self.merge_vocabulary(&count.vocabulary);
+    }
+
+    /// Folds another summary into this one.
+    pub fn merge(&mut self, other: &Summary) {
+        self.files += other.files;
+        self.total_words += other.total_words;
+        self.content_words += other.content_words;
+        self.cjk_characters += other.cjk_characters;
+        self.merge_vocabulary(&other.vocabulary);
+    }
+
+    fn merge_vocabulary(&mut self, vocabulary: &HashMap<String, usize>) {
+        for (word, occurrences) in vocabulary {
+            *self.vocabulary.entry(word.clone()).or_insert(0) += occurrences;
+        }
+    }
+
+    /// How often each word occurs across every file added.
+    pub fn vocabulary(&self) -> &HashMap<String, usize> {
+        &self.vocabulary
+    }
+
+    /// The `n` most frequent words across every file added.
+    pub fn top_words(&self, n: usize) -> Vec<WordFrequency> {
+        top_words(&self.vocabulary, self.content_words, n)
+    }
+
+    /// Number of distinct words across every file added.
+    pub fn unique_words(&self) -> usize {
+        self.vocabulary.len()
+    }
+
+    /// Unique words as a percentage of the words left after stop-word filtering (0 when no words
+    /// were counted).
+    pub fn unique_ratio(&self) -> f64 {
+        if self.content_words == 0 {
+            0.0
+        } else {
+            (self.unique_words() as f64 / self.content_words as f64) * 100.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Format;
+
+    fn count(words: &[&str]) -> WordCount {
+        let mut vocabulary = HashMap::new();
+        for word in words {
+            *vocabulary.entry(word.to_string()).or_insert(0) += 1;
+        }
+        WordCount {
+            file_path: "test.txt".to_string(),
+            format: Format::Text,
+            unique_words: vocabulary.len(),
+            total_words: words.len(),
+            content_words: words.len(),
+            cjk_characters: 0,
+            parts: Vec::new(),
+            vocabulary,
+        }
+    }
+
+    #[test]
+    fn test_top_words() {
+        let mut summary = Summary::new();
+        summary.add(&count(&["the", "cat", "the", "bat", "the", "cat"]));
+
+        let top = summary.top_words(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].word.as_str(), top[0].occurrences), ("the", 3));
+        assert_eq!(top[0].percent, 50.0);
+        assert_eq!((top[1].word.as_str(), top[1].occurrences), ("cat", 2));
+
+        // Equal counts are listed alphabetically.
+        let tied = count(&["b", "a", "c"]);
+        let words: Vec<String> = top_words(&tied.vocabulary, 3, 10)
+            .into_iter()
+            .map(|f| f.word)
+            .collect();
+        assert_eq!(words, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_summary_merges_vocabulary() {
+        let mut first = Summary::new();
+        first.add(&count(&["hello", "world"]));
+        let mut second = Summary::new();
+        second.add(&count(&["hello", "rust"]));
+
+        let mut total = Summary::new();
+        total.merge(&first);
+        total.merge(&second);
+        assert_eq!(total.files, 2);
+        assert_eq!(total.total_words, 4);
+        assert_eq!(total.unique_words(), 3);
+        assert_eq!(total.unique_ratio(), 75.0);
+        assert_eq!(total.vocabulary()["hello"], 2);
+        assert_eq!(Summary::new().unique_ratio(), 0.0);
+    }
+}
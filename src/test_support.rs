@@ -0,0 +1,56 @@
+//! Fixtures shared by the unit tests.
+
+use std::fs::File;
+use std::io::Write;
+
+use tempfile::TempDir;
+use zip::write::FileOptions;
+
+pub fn create_test_file(dir: &TempDir, filename: &str, content: &str) -> String {
+    let file_path = dir.path().join(filename);
+    let mut file = File::create(&file_path).unwrap();
+    writeln!(file, "{}", content).unwrap();
+    file_path.to_str().unwrap().to_string()
+}
+
+pub fn create_docx_file(dir: &TempDir, filename: &str, content: &str) -> String {
+    create_docx_with_parts(dir, filename, &[("word/document.xml", content)])
+}
+
+/// Writes a DOCX file with one paragraph of `content` in each of the given archive entries.
+pub fn create_docx_with_parts(dir: &TempDir, filename: &str, parts: &[(&str, &str)]) -> String {
+    // Wrap content in minimal XML
+    let entries: Vec<(&str, String)> = parts
+        .iter()
+        .map(|(entry, content)| {
+            let xml = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>
+            <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">
+            <w:body><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:body></w:document>",
+                content
+            );
+            (*entry, xml)
+        })
+        .collect();
+    let entries: Vec<(&str, &str)> = entries
+        .iter()
+        .map(|(entry, xml)| (*entry, xml.as_str()))
+        .collect();
+    create_zip_file(dir, filename, &entries)
+}
+
+/// Writes a ZIP archive (such as an ODT or EPUB file) with the given entries stored verbatim.
+pub fn create_zip_file(dir: &TempDir, filename: &str, entries: &[(&str, &str)]) -> String {
+    let file_path = dir.path().join(filename);
+    let file = File::create(&file_path).unwrap();
+    let mut zip = zip::ZipWriter::new(file);
+
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (entry, content) in entries {
+        zip.start_file(*entry, options).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    file_path.to_str().unwrap().to_string()
+}
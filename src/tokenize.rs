@@ -0,0 +1,162 @@
+//! Splitting extracted text into words.
+
+use std::fmt;
+use std::str::FromStr;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The word splitting rules used for counting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tokenizer {
+    /// Every non-alphabetic character is a separator. Digits are dropped and "don't" counts as
+    /// two words. This is the historical behaviour and stays the default so existing numbers
+    /// remain reproducible.
+    #[default]
+    Legacy,
+    /// Unicode word boundaries (UAX #29): contractions, numbers and words with combining marks
+    /// stay whole, and hyphenated compounds such as "state-of-the-art" count as one word.
+    Unicode,
+}
+
+impl fmt::Display for Tokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Tokenizer::Legacy => "legacy",
+            Tokenizer::Unicode => "unicode",
+        })
+    }
+}
+
+impl FromStr for Tokenizer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "legacy" => Ok(Tokenizer::Legacy),
+            "unicode" | "uax29" => Ok(Tokenizer::Unicode),
+            _ => Err(format!("Unknown tokenizer '{}'", s)),
+        }
+    }
+}
+
+/// Splits text into lowercase words using the given rules.
+pub fn tokenize(text: &str, tokenizer: Tokenizer) -> Vec<String> {
+    match tokenizer {
+        Tokenizer::Legacy => text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_lowercase())
+            .collect(),
+        Tokenizer::Unicode => unicode_words(text),
+    }
+}
+
+/// Returns true for Han ideographs and Japanese kana, which are counted one character at a time
+/// because CJK text has no spaces between words.
+pub fn is_cjk(c: char) -> bool {
+    c.is_alphabetic()
+        && matches!(c as u32,
+            0x3005 | 0x3007 | 0x303B           // 々 〇 〻
+            | 0x3040..=0x30FF                  // Hiragana, Katakana
+            | 0x31F0..=0x31FF                  // Katakana phonetic extensions
+            | 0x3400..=0x4DBF                  // CJK Extension A
+            | 0x4E00..=0x9FFF                  // CJK Unified Ideographs
+            | 0xF900..=0xFAFF                  // CJK Compatibility Ideographs
+            | 0xFF66..=0xFF9F                  // Halfwidth Katakana
+            | 0x20000..=0x323AF                // CJK Extensions B-H and supplements
+        )
+}
+
+/// Removes CJK characters from `text`, replacing each with a space so the surrounding words stay
+/// separate, and returns the remaining text with the number of characters removed.
+pub fn split_cjk(text: &str) -> (String, usize) {
+    let mut cjk_characters = 0;
+    let rest = text
+        .chars()
+        .map(|c| {
+            if is_cjk(c) {
+                cjk_characters += 1;
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    (rest, cjk_characters)
+}
+
+/// Returns true for UAX #29 segments that are words rather than spaces or punctuation.
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+/// Collects UAX #29 words, joining segments linked by a single hyphen with no surrounding space.
+fn unicode_words(text: &str) -> Vec<String> {
+    let segments: Vec<&str> = text.split_word_bounds().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut i = 0;
+
+    while i < segments.len() {
+        let segment = segments[i];
+        if is_word(segment) {
+            current.push_str(segment);
+            let hyphenated = matches!(segments.get(i + 1), Some(&"-") | Some(&"\u{2010}"))
+                && segments.get(i + 2).is_some_and(|next| is_word(next));
+            if hyphenated {
+                current.push_str(segments[i + 1]);
+                i += 2;
+                continue;
+            }
+            words.push(current.to_lowercase());
+            current.clear();
+        }
+        i += 1;
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Hello, WORLD! 42 times", Tokenizer::Legacy),
+            vec!["hello", "world", "times"]
+        );
+        assert!(tokenize("  123 -- ", Tokenizer::Legacy).is_empty());
+    }
+
+    #[test]
+    fn test_unicode_tokenizer() {
+        assert_eq!(
+            tokenize(
+                "Don't stop: state-of-the-art in 2024 - really.",
+                Tokenizer::Unicode
+            ),
+            vec!["don't", "stop", "state-of-the-art", "in", "2024", "really"]
+        );
+        // Devanagari combining marks stay inside their word.
+        assert_eq!(tokenize("नमस्ते दुनिया", Tokenizer::Unicode).len(), 2);
+        assert_eq!(tokenize("नमस्ते दुनिया", Tokenizer::Legacy).len(), 3);
+    }
+
+    #[test]
+    fn test_split_cjk() {
+        let (rest, count) = split_cjk("我们使用Rust编程。カタカナー and ひらがな");
+        assert_eq!(count, 15);
+        assert_eq!(tokenize(&rest, Tokenizer::Legacy), vec!["rust", "and"]);
+        // Punctuation such as the ideographic full stop and katakana middle dot is not counted.
+        assert_eq!(split_cjk("。・").1, 0);
+    }
+
+    #[test]
+    fn test_parse_tokenizer() {
+        assert_eq!("Unicode".parse::<Tokenizer>(), Ok(Tokenizer::Unicode));
+        assert_eq!("legacy".parse::<Tokenizer>(), Ok(Tokenizer::Legacy));
+        assert!("words".parse::<Tokenizer>().is_err());
+    }
+}
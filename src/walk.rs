@@ -0,0 +1,288 @@
+//! Expanding command-line inputs (glob patterns and directories) into lists of files.
+
+use std::error::Error;
+use std::path::Path;
+
+use glob::{glob, Pattern};
+use ignore::WalkBuilder;
+
+use crate::extract::SUPPORTED_EXTENSIONS;
+use crate::FileError;
+
+/// Controls which files are picked up when expanding an input.
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Paths (relative to the walked directory) or file names to skip. A matching directory is
+    /// not descended into.
+    pub exclude: Vec<Pattern>,
+    /// How deep to descend into a directory argument; 1 counts only the files directly inside
+    /// it. `None` walks the whole tree.
+    pub max_depth: Option<usize>,
+    /// File extensions to count when walking a directory, compared case-insensitively. Empty
+    /// means every extension mdwc has an extractor for.
+    pub extensions: Vec<String>,
+    /// Walk directories without consulting `.gitignore`, `.ignore`, `.git/info/exclude` or the
+    /// global git excludes file.
+    pub no_ignore: bool,
+}
+
+impl WalkOptions {
+    /// Adds an exclude pattern given on the command line.
+    pub fn add_exclude(&mut self, pattern: &str) -> Result<(), Box<dyn Error>> {
+        let compiled = Pattern::new(pattern)
+            .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        self.exclude.push(compiled);
+        Ok(())
+    }
+
+    /// Returns true if `path` (relative to the input it was found under) matches an exclude
+    /// pattern, either as a whole or by its file name.
+    fn is_excluded(&self, path: &Path) -> bool {
+        is_excluded(&self.exclude, path)
+    }
+
+    /// Returns true if a file found while walking a directory has an allowed extension.
+    fn is_allowed_extension(&self, path: &Path) -> bool {
+        let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+            return false;
+        };
+        if self.extensions.is_empty() {
+            SUPPORTED_EXTENSIONS
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(ext))
+        } else {
+            self.extensions
+                .iter()
+                .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
+        }
+    }
+}
+
+fn is_excluded(exclude: &[Pattern], path: &Path) -> bool {
+    let name = path.file_name().map(Path::new);
+    exclude.iter().any(|pattern| {
+        pattern.matches_path(path) || name.is_some_and(|name| pattern.matches_path(name))
+    })
+}
+
+/// Returns true for files mdwc never counts, such as temporary Word files (`~$name.docx`).
+fn is_skipped_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| name.starts_with("~$"))
+}
+
+/// Expands one input into the files to count, in a stable order. A directory is walked
+/// recursively; anything else is treated as a glob pattern. Unreadable entries are returned as
+/// errors alongside the files. Only an invalid pattern fails the whole input.
+pub fn expand_input(
+    input: &str,
+    options: &WalkOptions,
+) -> Result<(Vec<String>, Vec<FileError>), Box<dyn Error>> {
+    if Path::new(input).is_dir() {
+        Ok(walk_directory(Path::new(input), options))
+    } else {
+        expand_glob(input, options)
+    }
+}
+
+fn expand_glob(
+    pattern: &str,
+    options: &WalkOptions,
+) -> Result<(Vec<String>, Vec<FileError>), Box<dyn Error>> {
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+
+    for entry in glob(pattern)? {
+        match entry {
+            Ok(path) => {
+                if path.is_file() && !is_skipped_file(&path) && !options.is_excluded(&path) {
+                    paths.push(path.to_string_lossy().into_owned());
+                }
+            }
+            Err(e) => errors.push(FileError {
+                file_path: e.path().to_string_lossy().into_owned(),
+                message: e.error().to_string(),
+            }),
+        }
+    }
+
+    Ok((paths, errors))
+}
+
+/// Walks a directory in file name order. Unless `no_ignore` is set, paths ignored by git
+/// (nested `.gitignore` files, `.git/info/exclude` and the global excludes file) or by `.ignore`
+/// files are skipped, whether or not the directory is inside a git repository. Hidden files are
+/// counted, but `.git` directories never are.
+fn walk_directory(root: &Path, options: &WalkOptions) -> (Vec<String>, Vec<FileError>) {
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+
+    let respect_ignore = !options.no_ignore;
+    let exclude = options.exclude.clone();
+    let prefix = root.to_path_buf();
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .parents(respect_ignore)
+        .ignore(respect_ignore)
+        .git_ignore(respect_ignore)
+        .git_exclude(respect_ignore)
+        .git_global(respect_ignore)
+        .require_git(false)
+        .max_depth(options.max_depth)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let is_git_dir =
+                entry.file_name() == ".git" && entry.file_type().is_some_and(|t| t.is_dir());
+            let relative = entry.path().strip_prefix(&prefix).unwrap_or(entry.path());
+            !is_git_dir && !is_excluded(&exclude, relative)
+        })
+        .build();
+
+    for entry in walker {
+        match entry {
+            Ok(entry) => {
+                let path = entry.path();
+                if entry.file_type().is_some_and(|t| t.is_file())
+                    && !is_skipped_file(path)
+                    && options.is_allowed_extension(path)
+                {
+                    paths.push(path.to_string_lossy().into_owned());
+                }
+            }
+            Err(e) => errors.push(FileError {
+                file_path: error_path(&e)
+                    .unwrap_or(root)
+                    .to_string_lossy()
+                    .into_owned(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    (paths, errors)
+}
+
+/// The path a walk error refers to, if it carries one.
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        ignore::Error::Loop { child, .. } => Some(child),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_file;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn names(paths: &[String], root: &Path) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| {
+                Path::new(path)
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("guide/drafts")).unwrap();
+        create_test_file(&dir, "intro.md", "intro");
+        create_test_file(&dir, "logo.png", "not text");
+        create_test_file(&dir, "~$notes.docx", "lock file");
+        create_test_file(&dir, "guide/setup.TXT", "setup");
+        create_test_file(&dir, "guide/drafts/wip.md", "wip");
+
+        let root = dir.path();
+        let input = root.to_str().unwrap();
+        let (paths, errors) = expand_input(input, &WalkOptions::default()).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(
+            names(&paths, root),
+            vec!["guide/drafts/wip.md", "guide/setup.TXT", "intro.md"]
+        );
+
+        let mut options = WalkOptions {
+            max_depth: Some(2),
+            ..WalkOptions::default()
+        };
+        let (paths, _) = expand_input(input, &options).unwrap();
+        assert_eq!(names(&paths, root), vec!["guide/setup.TXT", "intro.md"]);
+
+        options.max_depth = None;
+        options.add_exclude("drafts").unwrap();
+        let (paths, _) = expand_input(input, &options).unwrap();
+        assert_eq!(names(&paths, root), vec!["guide/setup.TXT", "intro.md"]);
+
+        let options = WalkOptions {
+            extensions: vec![".png".to_string()],
+            ..WalkOptions::default()
+        };
+        let (paths, _) = expand_input(input, &options).unwrap();
+        assert_eq!(names(&paths, root), vec!["logo.png"]);
+        assert!(WalkOptions::default().add_exclude("[").is_err());
+    }
+
+    #[test]
+    fn test_walk_respects_ignore_files() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("docs/generated")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(dir.path().join(".github")).unwrap();
+        create_test_file(&dir, ".gitignore", "node_modules/\n*.log.md");
+        create_test_file(&dir, "docs/.ignore", "generated/");
+        create_test_file(&dir, "README.md", "readme");
+        create_test_file(&dir, "build.log.md", "log");
+        create_test_file(&dir, "node_modules/pkg/README.md", "vendored");
+        create_test_file(&dir, "docs/guide.md", "guide");
+        create_test_file(&dir, "docs/generated/api.md", "api");
+        create_test_file(&dir, ".git/notes.txt", "git internals");
+        create_test_file(&dir, ".github/CONTRIBUTING.md", "contributing");
+
+        let root = dir.path();
+        let input = root.to_str().unwrap();
+        let (paths, errors) = expand_input(input, &WalkOptions::default()).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(
+            names(&paths, root),
+            vec![".github/CONTRIBUTING.md", "README.md", "docs/guide.md"]
+        );
+
+        let options = WalkOptions {
+            no_ignore: true,
+            ..WalkOptions::default()
+        };
+        let (paths, _) = expand_input(input, &options).unwrap();
+        assert_eq!(paths.len(), 6);
+        assert!(!paths.iter().any(|path| path.contains(".git/")));
+    }
+
+    #[test]
+    fn test_exclude_applies_to_globs() {
+        let dir = TempDir::new().unwrap();
+        create_test_file(&dir, "a.md", "a");
+        create_test_file(&dir, "a.draft.md", "b");
+
+        let mut options = WalkOptions::default();
+        options.add_exclude("*.draft.md").unwrap();
+        let pattern = format!("{}/*.md", dir.path().to_str().unwrap());
+        let (paths, _) = expand_input(&pattern, &options).unwrap();
+        assert_eq!(names(&paths, dir.path()), vec!["a.md"]);
+    }
+}